@@ -1,4 +1,6 @@
-#[derive(PartialEq, Debug, Clone)]
+use serde::{Serialize, Deserialize};
+
+#[derive(PartialEq, Debug, Clone, Serialize, Deserialize)]
 pub struct Coordinates {
     pub x: f64,
     pub y: f64
@@ -22,6 +24,7 @@ impl Coordinates {
     }
 }
 
+#[derive(PartialEq, Debug, Clone, Serialize, Deserialize)]
 pub struct Size {
     pub width: f64,
     pub height: f64
@@ -47,12 +50,70 @@ pub fn check_if_point_is_contained_within_rectangle(point: &Coordinates, rectang
     return false;
 }
 
+// Rectangles which only touch each other's edges don't overlap.
+pub fn check_if_rectangles_overlap(a_position: &Coordinates, a_size: &Size, b_position: &Coordinates, b_size: &Size) -> bool {
+    return a_position.x < b_position.x + b_size.width && b_position.x < a_position.x + a_size.width
+        && a_position.y < b_position.y + b_size.height && b_position.y < a_position.y + a_size.height;
+}
+
+pub fn check_if_segment_intersects_rectangle(a: &Coordinates, b: &Coordinates, rectangle_upper_vertex: &Coordinates, size: &Size) -> bool {
+    if check_if_point_is_contained_within_rectangle(b, rectangle_upper_vertex, size) {
+        return true;
+    }
+
+    return get_segment_entry_distance(a, b, rectangle_upper_vertex, size).is_some();
+}
+
+// Distance from a to the first point of the segment a-b lying on the rectangle, 0 when a is inside it.
+pub fn get_segment_entry_distance(a: &Coordinates, b: &Coordinates, rectangle_upper_vertex: &Coordinates, size: &Size) -> Option<f64> {
+    if check_if_point_is_contained_within_rectangle(a, rectangle_upper_vertex, size) {
+        return Some(0.0);
+    }
+
+    let line_equation = LineEquation::get_line_equation(a, b);
+    let rectangle_line_equations = RectangleLineEquations::get_square_line_equations(rectangle_upper_vertex, size);
+    let intersection_points = RectangleIntersectionPoints::get(&line_equation, &rectangle_line_equations);
+
+    return intersection_points.get_points_on_rectangle(&rectangle_line_equations)
+        .iter()
+        .filter(|point| is_within_range(point.x, a.x.min(b.x), a.x.max(b.x)) && is_within_range(point.y, a.y.min(b.y), a.y.max(b.y)))
+        .map(|point| get_distance(a, point))
+        .fold(None, |closest: Option<f64>, distance| Some(closest.map_or(distance, |closest| closest.min(distance))));
+}
+
+// 0 for points inside the rectangle.
+pub fn get_distance_to_rectangle(point: &Coordinates, rectangle_upper_vertex: &Coordinates, size: &Size) -> f64 {
+    let x = point.x.max(rectangle_upper_vertex.x).min(rectangle_upper_vertex.x + size.width);
+    let y = point.y.max(rectangle_upper_vertex.y).min(rectangle_upper_vertex.y + size.height);
+    return get_distance(point, &Coordinates::new(x, y));
+}
+
+// Length of the gap between two rectangles, 0 when they touch or overlap.
+pub fn get_distance_between_rectangles(a_position: &Coordinates, a_size: &Size, b_position: &Coordinates, b_size: &Size) -> f64 {
+    let dx = (b_position.x - (a_position.x + a_size.width)).max(a_position.x - (b_position.x + b_size.width)).max(0.0);
+    let dy = (b_position.y - (a_position.y + a_size.height)).max(a_position.y - (b_position.y + b_size.height)).max(0.0);
+    return (dx * dx + dy * dy).sqrt();
+}
+
+// Rectangle spanned by two opposite corners given in any order.
+pub fn get_rectangle_from_corners(a: &Coordinates, b: &Coordinates) -> (Coordinates, Size) {
+    let position = Coordinates::new(a.x.min(b.x), a.y.min(b.y));
+    let size = Size::new((a.x - b.x).abs(), (a.y - b.y).abs());
+    return (position, size);
+}
+
 pub fn get_middle(position: &Coordinates, size: &Size) -> Coordinates {
     let x = position.x + size.width / 2.0;
     let y = position.y + size.height / 2.0;
     return Coordinates::new(x, y);
 }
 
+pub fn get_distance(a: &Coordinates, b: &Coordinates) -> f64 {
+    let x = b.x - a.x;
+    let y = b.y - a.y;
+    return (x * x + y * y).sqrt();
+}
+
 #[derive(PartialEq, Debug)]
 pub enum LineEquation {
     Vertical(f64), // x = constant
@@ -85,16 +146,16 @@ impl LineEquation {
         }
 
         let slope = (a.y - b.y) / (a.x - b.x);
-        let y_intercept = -1.0 * ((a.x * slope) - a.y);
+        let y_intercept = -((a.x * slope) - a.y);
 
         LineEquation::Curve{slope, y_intercept}
     }
 
     pub fn get_point_of_intersection(line_a: &LineEquation, line_b: &LineEquation) -> Option<Coordinates> {
         match line_a {
-            LineEquation::Vertical(x) => return LineEquation::calculate_intersection_for_vertical(*x, &line_b),
-            LineEquation::Horizontal(y) => return LineEquation::calculate_intersection_for_horizontal(*y, &line_b),
-            LineEquation::Curve{slope, y_intercept} => return LineEquation::calculate_intersection_for_curve(*slope, *y_intercept, &line_b)
+            LineEquation::Vertical(x) => return LineEquation::calculate_intersection_for_vertical(*x, line_b),
+            LineEquation::Horizontal(y) => return LineEquation::calculate_intersection_for_horizontal(*y, line_b),
+            LineEquation::Curve{slope, y_intercept} => return LineEquation::calculate_intersection_for_curve(*slope, *y_intercept, line_b)
         }
     }
 
@@ -123,7 +184,7 @@ impl LineEquation {
     }
 
     fn curve_curve_intersection(slope_a: f64, y_intercept_a: f64, slope_b: f64, y_intercept_b: f64) -> Coordinates {
-        let x = (y_intercept_a - y_intercept_b) / (slope_a - slope_b) * -1.0;
+        let x = -((y_intercept_a - y_intercept_b) / (slope_a - slope_b));
         let y = slope_a * x + y_intercept_a;
         Coordinates::new(x, y)
     }
@@ -134,7 +195,7 @@ impl LineEquation {
     }
 
     fn curve_y_intersection(slope: f64, y_intercept: f64, y: f64) -> Coordinates {
-        let x = (y_intercept - y) / slope * -1.0;
+        let x = -((y_intercept - y) / slope);
         Coordinates::new(x, y)
     }
 }
@@ -174,7 +235,7 @@ pub struct RectangleIntersectionPoints {
 }
 
 impl RectangleIntersectionPoints {
-    fn get(line_equation: &LineEquation, rectangle_line_equations: &RectangleLineEquations) -> RectangleIntersectionPoints {
+    pub fn get(line_equation: &LineEquation, rectangle_line_equations: &RectangleLineEquations) -> RectangleIntersectionPoints {
         let x_0 = LineEquation::get_point_of_intersection(line_equation, &rectangle_line_equations.x_0);
         let x_1 = LineEquation::get_point_of_intersection(line_equation, &rectangle_line_equations.x_1);
         let y_0 = LineEquation::get_point_of_intersection(line_equation, &rectangle_line_equations.y_0);
@@ -187,6 +248,32 @@ impl RectangleIntersectionPoints {
             y_1
         }
     }
+
+    // Keeps only the points that lie on the rectangle's edges, not just on the lines extending them.
+    pub fn get_points_on_rectangle(&self, rectangle_line_equations: &RectangleLineEquations) -> Vec<Coordinates> {
+        let (x_0, x_1, y_0, y_1) = rectangle_line_equations.to_floats();
+        let mut points = Vec::new();
+
+        for point in [&self.x_0, &self.x_1].iter().filter_map(|point| point.as_ref()) {
+            if is_within_range(point.y, y_0, y_1) {
+                points.push(point.clone());
+            }
+        }
+
+        for point in [&self.y_0, &self.y_1].iter().filter_map(|point| point.as_ref()) {
+            if is_within_range(point.x, x_0, x_1) {
+                points.push(point.clone());
+            }
+        }
+
+        return points;
+    }
+}
+
+const EPSILON: f64 = 0.000_001;
+
+fn is_within_range(value: f64, min: f64, max: f64) -> bool {
+    value >= min - EPSILON && value <= max + EPSILON
 }
 
 #[derive(PartialEq, Debug)]
@@ -358,6 +445,117 @@ mod tests {
         assert_eq!(square_line_equation, expected);
     }
 
+    #[test]
+    fn rectangle_intersection_points_get_points_on_rectangle_crossing_line_returns_two_points() {
+        let rectangle = RectangleLineEquations::get_square_line_equations(&Coordinates::new(50.0, 50.0), &Size::new(50.0, 50.0));
+        let line = LineEquation::Curve{slope: 1.0, y_intercept: 10.0};
+
+        let intersection_points = RectangleIntersectionPoints::get(&line, &rectangle);
+        let points = intersection_points.get_points_on_rectangle(&rectangle);
+        let expected = vec![Coordinates::new(50.0, 60.0), Coordinates::new(90.0, 100.0)];
+
+        assert_eq!(points, expected);
+    }
+
+    #[test]
+    fn rectangle_intersection_points_get_points_on_rectangle_missing_line_returns_no_points() {
+        let rectangle = RectangleLineEquations::get_square_line_equations(&Coordinates::new(50.0, 50.0), &Size::new(50.0, 50.0));
+        let line = LineEquation::Vertical(10.0);
+
+        let intersection_points = RectangleIntersectionPoints::get(&line, &rectangle);
+        let points = intersection_points.get_points_on_rectangle(&rectangle);
+
+        assert_eq!(points.is_empty(), true);
+    }
+
+    #[test]
+    fn check_if_rectangles_overlap_overlapping_rectangles_provided_returns_true() {
+        let result = check_if_rectangles_overlap(&Coordinates::new(0.0, 0.0), &Size::new(50.0, 50.0), &Coordinates::new(40.0, 40.0), &Size::new(50.0, 50.0));
+
+        assert_eq!(result, true);
+    }
+
+    #[test]
+    fn check_if_rectangles_overlap_touching_rectangles_provided_returns_false() {
+        let result = check_if_rectangles_overlap(&Coordinates::new(0.0, 0.0), &Size::new(50.0, 50.0), &Coordinates::new(50.0, 0.0), &Size::new(50.0, 50.0));
+
+        assert_eq!(result, false);
+    }
+
+    #[test]
+    fn check_if_segment_intersects_rectangle_crossing_segment_provided_returns_true() {
+        let a = Coordinates::new(0.0, 75.0);
+        let b = Coordinates::new(200.0, 75.0);
+
+        let result = check_if_segment_intersects_rectangle(&a, &b, &Coordinates::new(50.0, 50.0), &Size::new(50.0, 50.0));
+
+        assert_eq!(result, true);
+    }
+
+    #[test]
+    fn check_if_segment_intersects_rectangle_segment_ending_before_rectangle_provided_returns_false() {
+        let a = Coordinates::new(0.0, 75.0);
+        let b = Coordinates::new(40.0, 75.0);
+
+        let result = check_if_segment_intersects_rectangle(&a, &b, &Coordinates::new(50.0, 50.0), &Size::new(50.0, 50.0));
+
+        assert_eq!(result, false);
+    }
+
+    #[test]
+    fn check_if_segment_intersects_rectangle_segment_inside_rectangle_provided_returns_true() {
+        let a = Coordinates::new(60.0, 60.0);
+        let b = Coordinates::new(70.0, 80.0);
+
+        let result = check_if_segment_intersects_rectangle(&a, &b, &Coordinates::new(50.0, 50.0), &Size::new(50.0, 50.0));
+
+        assert_eq!(result, true);
+    }
+
+    #[test]
+    fn get_segment_entry_distance_crossing_segment_provided_returns_distance_to_closest_edge() {
+        let a = Coordinates::new(0.0, 75.0);
+        let b = Coordinates::new(200.0, 75.0);
+
+        let result = get_segment_entry_distance(&a, &b, &Coordinates::new(50.0, 50.0), &Size::new(50.0, 50.0));
+
+        assert_eq!(result, Some(50.0));
+    }
+
+    #[test]
+    fn get_distance_to_rectangle_point_diagonally_outside_returns_distance_to_corner() {
+        let point = Coordinates::new(0.0, 0.0);
+
+        let result = get_distance_to_rectangle(&point, &Coordinates::new(3.0, 4.0), &Size::new(10.0, 10.0));
+
+        assert_eq!(result, 5.0);
+    }
+
+    #[test]
+    fn get_distance_between_rectangles_diagonal_gap_returns_distance_between_closest_corners() {
+        let result = get_distance_between_rectangles(&Coordinates::new(0.0, 0.0), &Size::new(10.0, 10.0), &Coordinates::new(13.0, 14.0), &Size::new(10.0, 10.0));
+
+        assert_eq!(result, 5.0);
+    }
+
+    #[test]
+    fn get_rectangle_from_corners_corners_in_reverse_order_provided_returns_correct_rectangle() {
+        let (position, size) = get_rectangle_from_corners(&Coordinates::new(50.0, 10.0), &Coordinates::new(20.0, 40.0));
+
+        assert_eq!(position, Coordinates::new(20.0, 10.0));
+        assert_eq!((size.width, size.height), (30.0, 30.0));
+    }
+
+    #[test]
+    fn get_distance_returns_correct_distance() {
+        let a = Coordinates::new(2.0, 3.0);
+        let b = Coordinates::new(5.0, 7.0);
+
+        let distance = get_distance(&a, &b);
+
+        assert_eq!(distance, 5.0);
+    }
+
     #[test]
     fn get_middle_returns_correct_coordinates() {
         let position = Coordinates::new(0.0, 0.0);
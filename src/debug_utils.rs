@@ -1,20 +1,26 @@
 use std::time;
 use std::io;
 use std::str::FromStr;
-use std::error::Error;
 use std::fmt;
-use std::num;
-use std::io::prelude::*;
+use std::mem;
 
 use crate::algebra_basics::{Coordinates};
 use crate::game_controller::game_managers::{BasicState, UserInput};
 use crate::game_data::gameboard::GameboardObjectOperation;
+use crate::game_controller::snapshot;
+use crate::game_controller::ai::AiState;
+use crate::game_controller::pathfinder::Pathfinder;
+use crate::game_controller::line_of_sight::{self, LineOfSight};
 
 #[derive(PartialEq, Debug)]
 pub enum ConsoleCommand {
     None,
     Disable,
-    MoveObject{id: u32, new_position: Coordinates}
+    MoveObject{id: u32, new_position: Coordinates},
+    RemoveObject{id: u32},
+    Save{path: String},
+    Load{path: String},
+    SelectPathfinder{pathfinder: Pathfinder}
 }
 
 #[derive(PartialEq, Debug)]
@@ -34,7 +40,8 @@ pub trait DebugStateContainer {
 #[derive(PartialEq, Debug)]
 pub struct LineObject {
     pub a: Coordinates,
-    pub b: Coordinates
+    pub b: Coordinates,
+    pub line_of_sight: LineOfSight
 }
 
 pub fn process_debug_line(basic_state: &BasicState, mut debug_state: DebugState) -> DebugState {
@@ -44,7 +51,8 @@ pub fn process_debug_line(basic_state: &BasicState, mut debug_state: DebugState)
     if let (Some(cursor_pos), Some(object_pos)) = (cursor_pos, current_selected_coordinates) {
         debug_state.debug_line = LineObject {
             a: object_pos.clone(),
-            b: cursor_pos.clone()
+            b: cursor_pos.clone(),
+            line_of_sight: line_of_sight::line_of_sight(object_pos, cursor_pos, &basic_state.gameboard)
         }
     }
 
@@ -62,7 +70,7 @@ pub fn process_debug_enabled(basic_state: &BasicState, mut debug_state: DebugSta
 }
 
 pub fn print_object_positions_and_sizes(basic_state: &BasicState, debug_state: &mut DebugState) {
-    if debug_state.debug_prints_enabled == false {
+    if !debug_state.debug_prints_enabled {
         return;
     }
 
@@ -71,9 +79,10 @@ pub fn print_object_positions_and_sizes(basic_state: &BasicState, debug_state: &
     }
 
     debug_state.last_print_time = time::SystemTime::now();
-    let objects = &basic_state.gameboard.game_objects;
-
-    for (id, object) in objects {
+    // Enemies hidden in the fog of war aren't printed either.
+    let gameboard = &basic_state.gameboard;
+    for id in gameboard.get_objects_visible_to(basic_state.local_team) {
+        let object = gameboard.get_object_by_id(id).unwrap();
         println!("Id: {}, Position: x: {}, y: {} | size: width: {}, height: {}",
             id,
             object.position.x,
@@ -88,9 +97,9 @@ pub fn print_object_positions_and_sizes(basic_state: &BasicState, debug_state: &
 }
 
 fn current_selected_pos(basic_state: &BasicState) -> Option<&Coordinates> {
-    let current_id = basic_state.current_selected_id;
+    let current_id = basic_state.selected_ids.iter().next()?;
 
-    if let Some(object) = basic_state.gameboard.get_object_by_id(current_id) {
+    if let Some(object) = basic_state.gameboard.get_object_by_id(*current_id) {
         return Some(&object.position);
     }
 
@@ -151,7 +160,7 @@ fn parse_command(mut command: String) -> Result<ConsoleCommand, ConsoleCommandEr
     
     let args: Vec<&str> = command.split(" ").collect();
     
-    if args.len() == 0 {
+    if args.is_empty() {
         return Ok(ConsoleCommand::None);
     }
 
@@ -161,14 +170,30 @@ fn parse_command(mut command: String) -> Result<ConsoleCommand, ConsoleCommandEr
             let id: u32 = parse_arg(&args[1..])?;
             let x: f64 = parse_arg(&args[2..])?;
             let y: f64 = parse_arg(&args[3..])?;
-            return Ok(ConsoleCommand::MoveObject{id: id, new_position: Coordinates::new(x, y)});
+            return Ok(ConsoleCommand::MoveObject{id, new_position: Coordinates::new(x, y)});
+        }
+        "remove" => {
+            let id: u32 = parse_arg(&args[1..])?;
+            return Ok(ConsoleCommand::RemoveObject{id});
+        }
+        "save" => {
+            let path: String = parse_arg(&args[1..])?;
+            return Ok(ConsoleCommand::Save{path});
+        }
+        "load" => {
+            let path: String = parse_arg(&args[1..])?;
+            return Ok(ConsoleCommand::Load{path});
+        }
+        "pathfinder" => {
+            let pathfinder: Pathfinder = parse_arg(&args[1..])?;
+            return Ok(ConsoleCommand::SelectPathfinder{pathfinder});
         }
         _ => return Err(ConsoleCommandError::UnknownCommand)
     }
 }
 
 fn parse_arg<T: FromStr>(args: &[&str]) -> Result<T, ConsoleCommandError> {
-    if let Some(arg) = args.get(0) {
+    if let Some(arg) = args.first() {
         let parsing_result = T::from_str(arg);
         if let Ok(parsed_arg) =  parsing_result {
             return Ok(parsed_arg);
@@ -178,15 +203,76 @@ fn parse_arg<T: FromStr>(args: &[&str]) -> Result<T, ConsoleCommandError> {
     return Err(ConsoleCommandError::NotEnoughArguments);
 }
 
-pub fn apply_console_command_to_basic_state(mut state: BasicState, debug_state: &DebugState) -> BasicState {
+// Each command runs once, otherwise a load would keep reverting the game until the next command is typed.
+pub fn apply_console_command_to_basic_state(state: &mut BasicState, ai_state: &mut AiState, debug_state: &mut DebugState) -> Result<(), String> {
     if !debug_state.console_commands_enabled {
-        return state;
+        return Ok(());
     }
-    
-    match &debug_state.last_command {
-        ConsoleCommand::MoveObject{id, new_position} => state.gameboard.execute_operation(*id, GameboardObjectOperation::Move(new_position.clone())),
-        _ => {}
+
+    // Disable is left for process_console_command to handle.
+    if debug_state.last_command == ConsoleCommand::Disable {
+        return Ok(());
     }
 
-    return state;
-}
\ No newline at end of file
+    match mem::replace(&mut debug_state.last_command, ConsoleCommand::None) {
+        ConsoleCommand::MoveObject{id, new_position} => state.gameboard
+            .execute_operation(id, GameboardObjectOperation::Move(new_position))
+            .map_err(|error| error.to_string()),
+        ConsoleCommand::RemoveObject{id} => state.remove_object(id).map_err(|error| error.to_string()),
+        ConsoleCommand::Save{path} => snapshot::save_to_file(state, ai_state, &path).map_err(|error| error.to_string()),
+        ConsoleCommand::Load{path} => snapshot::load_from_file(state, &path)
+            .map(|loaded| ai_state.load(loaded))
+            .map_err(|error| error.to_string()),
+        ConsoleCommand::SelectPathfinder{pathfinder} => {
+            state.pathfinder = pathfinder;
+            Ok(())
+        },
+        _ => Ok(())
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use std::env;
+    use crate::testing::setup;
+
+    fn setup_debug_state_with_command(command: ConsoleCommand) -> DebugState {
+        DebugState {
+            debug_line: LineObject{a: Coordinates::new(0.0, 0.0), b: Coordinates::new(0.0, 0.0), line_of_sight: LineOfSight::Clear},
+            debug_prints_enabled: false,
+            console_commands_enabled: true,
+            last_print_time: time::SystemTime::now(),
+            debug_tick_time: time::Duration::new(20, 0),
+            last_command: command
+        }
+    }
+
+    #[test]
+    fn apply_console_command_to_basic_state_load_applied_twice_loads_once() {
+        let mut state = setup::setup_game_state_with_one_object();
+        let path = env::temp_dir().join("tactical_squares_console_load.json").to_string_lossy().to_string();
+        snapshot::save_to_file(&state.basic_state, &state.ai_state, &path).unwrap();
+        let mut debug_state = setup_debug_state_with_command(ConsoleCommand::Load{path});
+
+        apply_console_command_to_basic_state(&mut state.basic_state, &mut state.ai_state, &mut debug_state).unwrap();
+        state.basic_state.gameboard.execute_operation(0, GameboardObjectOperation::Move(Coordinates::new(100.0, 100.0))).unwrap();
+        apply_console_command_to_basic_state(&mut state.basic_state, &mut state.ai_state, &mut debug_state).unwrap();
+
+        assert_eq!(state.basic_state.gameboard.get_object_by_id(0).unwrap().position, Coordinates::new(100.0, 100.0));
+        assert_eq!(debug_state.last_command, ConsoleCommand::None);
+    }
+
+    #[test]
+    fn apply_console_command_to_basic_state_remove_applied_twice_no_error() {
+        let mut state = setup::setup_game_state_with_one_object();
+        let mut debug_state = setup_debug_state_with_command(ConsoleCommand::RemoveObject{id: 0});
+
+        let first = apply_console_command_to_basic_state(&mut state.basic_state, &mut state.ai_state, &mut debug_state);
+        let second = apply_console_command_to_basic_state(&mut state.basic_state, &mut state.ai_state, &mut debug_state);
+
+        assert_eq!(first, Ok(()));
+        assert_eq!(second, Ok(()));
+        assert_eq!(state.basic_state.gameboard.get_object_by_id(0).is_none(), true);
+    }
+}
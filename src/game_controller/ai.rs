@@ -0,0 +1,541 @@
+use std::collections::BTreeMap;
+use std::fmt;
+use std::rc::Rc;
+
+use serde::{Serialize, Deserialize};
+
+use crate::algebra_basics::{Coordinates, Vector};
+use crate::algebra_basics;
+use crate::game_data::gameboard::Gameboard;
+use crate::game_data::components::{AiBehaviour, BehaviourMode, ComponentKind, Health, Interaction};
+use crate::game_controller::game_managers::{BasicState, BasicStateContainer, UserInput};
+use crate::game_controller::game_managers;
+use crate::game_controller::turns::GameMode;
+use crate::game_controller::combat;
+use crate::game_controller::interactions;
+
+// Seconds between two decisions of a unit in real time. A random delay is added on top,
+// so that the units don't all react in the same iteration.
+const THINK_INTERVAL: f64 = 0.5;
+const THINK_JITTER: f64 = 0.25;
+const RETREAT_DISTANCE: f64 = 100.0;
+// How close to a post or a waypoint a unit has to be to count as standing on it.
+const ARRIVAL_TOLERANCE: f64 = 5.0;
+
+// Xorshift generator, the same seed always leads to the same decisions.
+#[derive(Debug, PartialEq, Clone, Serialize, Deserialize)]
+pub struct AiRandom {
+    state: u64
+}
+
+impl AiRandom {
+    pub fn new(seed: u64) -> AiRandom {
+        // The generator would only ever return 0 with a zero state.
+        AiRandom {
+            state: (seed ^ 0x9E37_79B9_7F4A_7C15).max(1)
+        }
+    }
+
+    // In [0, 1).
+    pub fn next_f64(&mut self) -> f64 {
+        let mut x = self.state;
+        x ^= x << 13;
+        x ^= x >> 7;
+        x ^= x << 17;
+        self.state = x;
+
+        (x >> 11) as f64 / (1u64 << 53) as f64
+    }
+}
+
+// Saved in snapshots, so that a loaded game goes on the way it would have.
+#[derive(Debug, PartialEq, Clone, Serialize, Deserialize)]
+pub struct AiState {
+    random: AiRandom,
+    think_timers: BTreeMap<u32, f64>, // unit id to seconds left until it decides again
+    played_turn: Option<(u32, u32)>, // team and number of the last turn played in the turn-based mode
+    #[serde(skip)]
+    pub behaviours: BehaviourRegistry
+}
+
+impl AiState {
+    pub fn new(seed: u64) -> AiState {
+        AiState {
+            random: AiRandom::new(seed),
+            think_timers: BTreeMap::new(),
+            played_turn: None,
+            behaviours: BehaviourRegistry::default()
+        }
+    }
+
+    // Takes over a saved state, the registered behaviours aren't saved and stay as they are.
+    pub fn load(&mut self, saved: AiState) {
+        let behaviours = std::mem::take(&mut self.behaviours);
+        *self = saved;
+        self.behaviours = behaviours;
+    }
+
+    // Units whose timer ran out, in ascending order.
+    fn tick(&mut self, unit_ids: &[u32], time: f64) -> Vec<u32> {
+        self.think_timers.retain(|id, _| unit_ids.contains(id));
+
+        let mut ready = Vec::new();
+        for id in unit_ids {
+            let random = &mut self.random;
+            let timer = self.think_timers.entry(*id).or_insert_with(|| random.next_f64() * THINK_JITTER);
+            *timer -= time;
+
+            if *timer <= 0.0 {
+                *timer = THINK_INTERVAL + self.random.next_f64() * THINK_JITTER;
+                ready.push(*id);
+            }
+        }
+
+        return ready;
+    }
+}
+
+pub trait AiStateContainer {
+    fn get_ai_state(&mut self) -> &mut AiState;
+}
+
+// What a computer controlled unit does when it gets to decide, enemy_id is the nearest enemy its team sees.
+// Behaviours other than the built-in ones are registered by name and picked with BehaviourMode::Custom.
+pub trait Behaviour {
+    fn decide(&self, basic_state: &mut BasicState, id: u32, enemy_id: Option<u32>);
+}
+
+#[derive(Clone, Default)]
+pub struct BehaviourRegistry {
+    behaviours: BTreeMap<String, Rc<dyn Behaviour>>
+}
+
+impl BehaviourRegistry {
+    pub fn register(&mut self, name: &str, behaviour: Rc<dyn Behaviour>) {
+        self.behaviours.insert(name.to_string(), behaviour);
+    }
+
+    pub fn get(&self, name: &str) -> Option<Rc<dyn Behaviour>> {
+        self.behaviours.get(name).cloned()
+    }
+}
+
+// Behaviours are code, so only their names are compared and printed.
+impl PartialEq for BehaviourRegistry {
+    fn eq(&self, other: &BehaviourRegistry) -> bool {
+        self.behaviours.keys().eq(other.behaviours.keys())
+    }
+}
+
+impl fmt::Debug for BehaviourRegistry {
+    fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
+        f.debug_list().entries(self.behaviours.keys()).finish()
+    }
+}
+
+// Gives orders to the units of every team other than the local one which have a behaviour.
+// In the turn-based mode each of them decides once, at the start of the turn of its team.
+pub fn process_ai<S>(mut state: S) -> S
+where S: BasicStateContainer + AiStateContainer {
+    let basic_state = state.get_basic_state();
+
+    let time = match basic_state.external_event {
+        UserInput::Update(time) => time,
+        _ => return state
+    };
+
+    let local_team = basic_state.local_team;
+    let unit_ids: Vec<u32> = basic_state.gameboard.get_objects_with_components(&[ComponentKind::Team, ComponentKind::AiBehaviour])
+        .into_iter()
+        .filter(|id| basic_state.gameboard.get_team(*id) != Some(local_team))
+        .collect();
+    let turn = match &basic_state.game_mode {
+        GameMode::TurnBased(turn) => Some((turn.teams[turn.active], turn.number)),
+        GameMode::RealTime => None
+    };
+
+    let ai_state = state.get_ai_state();
+    let ready = match turn {
+        None => ai_state.tick(&unit_ids, time),
+        Some((team, _)) if team == local_team => Vec::new(),
+        Some(turn) if ai_state.played_turn == Some(turn) => Vec::new(),
+        Some(turn) => {
+            ai_state.played_turn = Some(turn);
+            let gameboard = &state.get_basic_state().gameboard;
+            unit_ids.into_iter().filter(|id| gameboard.get_team(*id) == Some(turn.0)).collect()
+        }
+    };
+
+    let behaviours = state.get_ai_state().behaviours.clone();
+    let basic_state = state.get_basic_state();
+    for id in ready {
+        decide(basic_state, &behaviours, id);
+    }
+
+    return state;
+}
+
+fn decide(basic_state: &mut BasicState, behaviours: &BehaviourRegistry, id: u32) {
+    let component = match basic_state.gameboard.get_component::<AiBehaviour>(id) {
+        Some(component) => component.clone(),
+        None => return
+    };
+    let enemy_id = get_nearest_visible_enemy(&basic_state.gameboard, id);
+
+    if let Some(enemy_id) = enemy_id {
+        if check_if_health_is_low(&basic_state.gameboard, id, component.retreat_below) {
+            retreat(basic_state, id, enemy_id);
+            return;
+        }
+    }
+
+    // Units with an unregistered custom behaviour stand still.
+    if let Some(behaviour) = get_behaviour(&component.mode, behaviours) {
+        behaviour.decide(basic_state, id, enemy_id);
+    }
+}
+
+fn get_behaviour(mode: &BehaviourMode, behaviours: &BehaviourRegistry) -> Option<Rc<dyn Behaviour>> {
+    match mode {
+        BehaviourMode::Idle => Some(Rc::new(Idle)),
+        BehaviourMode::Guard{post, radius} => Some(Rc::new(Guard{post: post.clone(), radius: *radius})),
+        BehaviourMode::Patrol{waypoints, next} => Some(Rc::new(Patrol{waypoints: waypoints.clone(), next: *next})),
+        BehaviourMode::Chase => Some(Rc::new(Chase)),
+        BehaviourMode::Capture => Some(Rc::new(Capture)),
+        BehaviourMode::Custom{name} => behaviours.get(name)
+    }
+}
+
+// Closest enemy with health the team of the unit can see. Ties go to the lower id.
+fn get_nearest_visible_enemy(gameboard: &Gameboard, id: u32) -> Option<u32> {
+    let (unit, team) = match (gameboard.get_object_by_id(id), gameboard.get_team(id)) {
+        (Some(unit), Some(team)) => (unit, team),
+        _ => return None
+    };
+
+    return gameboard.get_hostile_objects(team)
+        .into_iter()
+        .filter(|enemy_id| gameboard.get_component::<Health>(*enemy_id).is_some())
+        .filter(|enemy_id| gameboard.check_if_visible_to(team, *enemy_id))
+        .map(|enemy_id| {
+            let enemy = gameboard.get_object_by_id(enemy_id).unwrap();
+            (enemy_id, algebra_basics::get_distance_between_rectangles(&unit.position, &unit.size, &enemy.position, &enemy.size))
+        })
+        .min_by(|a, b| a.1.partial_cmp(&b.1).unwrap().then(a.0.cmp(&b.0)))
+        .map(|(enemy_id, _)| enemy_id);
+}
+
+fn check_if_health_is_low(gameboard: &Gameboard, id: u32, retreat_below: f64) -> bool {
+    return gameboard.get_component::<Health>(id).is_some_and(|health| health.current < health.max * retreat_below);
+}
+
+// Runs straight away from the enemy.
+fn retreat(basic_state: &mut BasicState, id: u32, enemy_id: u32) {
+    let gameboard = &basic_state.gameboard;
+    let (unit, enemy) = match (gameboard.get_object_by_id(id), gameboard.get_object_by_id(enemy_id)) {
+        (Some(unit), Some(enemy)) => (unit, enemy),
+        _ => return
+    };
+
+    let from = algebra_basics::get_middle(&enemy.position, &enemy.size);
+    let to = algebra_basics::get_middle(&unit.position, &unit.size);
+    if algebra_basics::get_distance(&from, &to) == 0.0 {
+        return;
+    }
+
+    let unit_vector = Vector::to_unit_vector(&Vector::get_vector(&from, &to));
+    let destination = Coordinates::new(unit.position.x + unit_vector.x * RETREAT_DISTANCE, unit.position.y + unit_vector.y * RETREAT_DISTANCE);
+    game_managers::order_move(basic_state, &[id], &destination);
+}
+
+// In the turn-based mode a unit out of range walks up to the target instead, attacking would waste its points.
+fn attack(basic_state: &mut BasicState, id: u32, target_id: u32) {
+    if let GameMode::TurnBased(_) = basic_state.game_mode {
+        if combat::check_if_within_weapon_range(&basic_state.gameboard, id, target_id) {
+            game_managers::order_attack(basic_state, &[id], target_id);
+            return;
+        }
+
+        let gameboard = &basic_state.gameboard;
+        let (unit, target) = match (gameboard.get_object_by_id(id), gameboard.get_object_by_id(target_id)) {
+            (Some(unit), Some(target)) => (unit, target),
+            _ => return
+        };
+        let adjacent = interactions::get_adjacent_position(&unit.position, &unit.size, target);
+        game_managers::order_move(basic_state, &[id], &adjacent);
+        return;
+    }
+
+    if basic_state.attack_orders.get(&id) != Some(&target_id) {
+        game_managers::order_attack(basic_state, &[id], target_id);
+    }
+}
+
+struct Idle;
+
+impl Behaviour for Idle {
+    fn decide(&self, _: &mut BasicState, _: u32, _: Option<u32>) {}
+}
+
+// Attacks enemies coming within the radius of the post, and walks back to it afterwards.
+struct Guard {
+    post: Coordinates,
+    radius: f64
+}
+
+impl Behaviour for Guard {
+    fn decide(&self, basic_state: &mut BasicState, id: u32, enemy_id: Option<u32>) {
+        let intruder_id = enemy_id.filter(|enemy_id| {
+            let enemy = basic_state.gameboard.get_object_by_id(*enemy_id).unwrap();
+            algebra_basics::get_distance_to_rectangle(&self.post, &enemy.position, &enemy.size) <= self.radius
+        });
+
+        if let Some(intruder_id) = intruder_id {
+            attack(basic_state, id, intruder_id);
+            return;
+        }
+
+        let position = match basic_state.gameboard.get_object_by_id(id) {
+            Some(unit) => unit.position.clone(),
+            None => return
+        };
+
+        if algebra_basics::get_distance(&position, &self.post) <= ARRIVAL_TOLERANCE {
+            basic_state.attack_orders.remove(&id);
+            return;
+        }
+
+        if !basic_state.movements.contains_key(&id) || basic_state.attack_orders.contains_key(&id) {
+            game_managers::order_move(basic_state, &[id], &self.post);
+        }
+    }
+}
+
+// Heads for the next waypoint once the unit stands still, and moves on to the one after it on arrival.
+struct Patrol {
+    waypoints: Vec<Coordinates>,
+    next: usize
+}
+
+impl Behaviour for Patrol {
+    fn decide(&self, basic_state: &mut BasicState, id: u32, _: Option<u32>) {
+        if self.waypoints.is_empty() || basic_state.movements.contains_key(&id) {
+            return;
+        }
+
+        let position = match basic_state.gameboard.get_object_by_id(id) {
+            Some(unit) => unit.position.clone(),
+            None => return
+        };
+
+        let mut next = self.next % self.waypoints.len();
+        if algebra_basics::get_distance(&position, &self.waypoints[next]) <= ARRIVAL_TOLERANCE {
+            next = (next + 1) % self.waypoints.len();
+        }
+
+        if let Some(behaviour) = basic_state.gameboard.get_component_mut::<AiBehaviour>(id) {
+            if let BehaviourMode::Patrol{next: current, ..} = &mut behaviour.mode {
+                *current = next;
+            }
+        }
+
+        game_managers::order_move(basic_state, &[id], &self.waypoints[next]);
+    }
+}
+
+struct Chase;
+
+impl Behaviour for Chase {
+    fn decide(&self, basic_state: &mut BasicState, id: u32, enemy_id: Option<u32>) {
+        if let Some(enemy_id) = enemy_id {
+            attack(basic_state, id, enemy_id);
+        }
+    }
+}
+
+// Fights the enemies it sees, and otherwise walks up to the nearest capture point its team doesn't hold to take it.
+struct Capture;
+
+impl Behaviour for Capture {
+    fn decide(&self, basic_state: &mut BasicState, id: u32, enemy_id: Option<u32>) {
+        if let Some(enemy_id) = enemy_id {
+            attack(basic_state, id, enemy_id);
+            return;
+        }
+
+        let target_id = match get_nearest_capture_point_to_take(&basic_state.gameboard, id) {
+            Some(target_id) => target_id,
+            None => return
+        };
+
+        if basic_state.interaction_orders.get(&id) != Some(&target_id) {
+            game_managers::order_interact(basic_state, &[id], target_id);
+        }
+    }
+}
+
+// Capture points held by a unit which died count as not held by anybody.
+fn get_nearest_capture_point_to_take(gameboard: &Gameboard, id: u32) -> Option<u32> {
+    let (unit, team) = match (gameboard.get_object_by_id(id), gameboard.get_team(id)) {
+        (Some(unit), Some(team)) => (unit, team),
+        _ => return None
+    };
+
+    let mut ids: Vec<u32> = gameboard.iter().map(|(id, _)| *id).collect();
+    ids.sort();
+
+    return ids.into_iter()
+        .filter(|target_id| match gameboard.get_component::<Interaction>(*target_id) {
+            Some(Interaction::CapturePoint{captured_by}) => captured_by.and_then(|holder| gameboard.get_team(holder)) != Some(team),
+            _ => false
+        })
+        .map(|target_id| {
+            let target = gameboard.get_object_by_id(target_id).unwrap();
+            (target_id, algebra_basics::get_distance_between_rectangles(&unit.position, &unit.size, &target.position, &target.size))
+        })
+        .min_by(|a, b| a.1.partial_cmp(&b.1).unwrap().then(a.0.cmp(&b.0)))
+        .map(|(target_id, _)| target_id);
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use crate::testing::setup;
+    use crate::algebra_basics::Size;
+    use crate::game_data::components::{Team, Weapon, ActionPoints};
+    use crate::game_data::game_object::{GameObject, GameObjectType};
+
+    const AI_TEAM: u32 = setup::LOCAL_TEAM + 1;
+
+    // The local unit 0 can be hurt, the computer controlled unit stands at the position.
+    fn setup_state_with_ai_unit_at(position: Coordinates, mode: BehaviourMode) -> (setup::MockMainState, u32) {
+        let mut state = setup::setup_game_state_with_one_object();
+        state.ai_state = AiState::new(7);
+        let basic_state = &mut state.basic_state;
+        basic_state.gameboard.get_object_by_id_mut(0).unwrap().components.insert(Health{current: 100.0, max: 100.0});
+
+        let mut unit = setup::setup_selectable_object_at(position);
+        unit.components.insert(Team{id: AI_TEAM});
+        unit.components.insert(Health{current: 100.0, max: 100.0});
+        unit.components.insert(Weapon{damage: 10.0, range: 20.0});
+        unit.components.insert(AiBehaviour{mode, retreat_below: 0.5});
+        let id = basic_state.gameboard.add_object(unit).unwrap();
+        basic_state.external_event = UserInput::Update(1.0);
+
+        (state, id)
+    }
+
+    #[test]
+    fn ai_random_same_seed_same_numbers() {
+        let mut a = AiRandom::new(42);
+        let mut b = AiRandom::new(42);
+
+        let a: Vec<f64> = (0..5).map(|_| a.next_f64()).collect();
+        let b: Vec<f64> = (0..5).map(|_| b.next_f64()).collect();
+
+        assert_eq!(a, b);
+        assert_eq!(a.iter().all(|number| (0.0..1.0).contains(number)), true);
+    }
+
+    #[test]
+    fn process_ai_chase_visible_enemy_attack_order_given() {
+        let (state, id) = setup_state_with_ai_unit_at(Coordinates::new(300.0, 300.0), BehaviourMode::Chase);
+
+        let state = process_ai(state);
+
+        assert_eq!(state.basic_state.attack_orders.get(&id), Some(&0));
+    }
+
+    #[test]
+    fn process_ai_guard_enemy_outside_radius_no_attack() {
+        let post = Coordinates::new(300.0, 300.0);
+        let (state, id) = setup_state_with_ai_unit_at(post.clone(), BehaviourMode::Guard{post, radius: 100.0});
+
+        let state = process_ai(state);
+
+        assert_eq!(state.basic_state.attack_orders.contains_key(&id), false);
+        assert_eq!(state.basic_state.movements.contains_key(&id), false);
+    }
+
+    #[test]
+    fn process_ai_patrol_on_waypoint_heads_for_next_one() {
+        let waypoints = vec![Coordinates::new(300.0, 300.0), Coordinates::new(400.0, 300.0)];
+        let (state, id) = setup_state_with_ai_unit_at(Coordinates::new(300.0, 300.0), BehaviourMode::Patrol{waypoints, next: 0});
+
+        let state = process_ai(state);
+
+        assert_eq!(state.basic_state.movements[&id].get_destination(), Some(&Coordinates::new(400.0, 300.0)));
+        let behaviour = state.basic_state.gameboard.get_component::<AiBehaviour>(id).unwrap();
+        assert_eq!(behaviour.mode, BehaviourMode::Patrol{waypoints: vec![Coordinates::new(300.0, 300.0), Coordinates::new(400.0, 300.0)], next: 1});
+    }
+
+    #[test]
+    fn process_ai_low_health_unit_runs_away_from_enemy() {
+        let (mut state, id) = setup_state_with_ai_unit_at(Coordinates::new(100.0, 0.0), BehaviourMode::Chase);
+        state.basic_state.gameboard.get_component_mut::<Health>(id).unwrap().current = 10.0;
+
+        let state = process_ai(state);
+
+        assert_eq!(state.basic_state.attack_orders.contains_key(&id), false);
+        assert_eq!(state.basic_state.movements[&id].get_destination(), Some(&Coordinates::new(200.0, 0.0)));
+    }
+
+    #[test]
+    fn process_ai_turn_based_unit_decides_once_per_turn() {
+        let (mut state, id) = setup_state_with_ai_unit_at(Coordinates::new(300.0, 0.0), BehaviourMode::Chase);
+        state.basic_state.gameboard.get_object_by_id_mut(id).unwrap().components.insert(ActionPoints{current: 0.0, max: 10.0});
+        let mut game_mode = GameMode::turn_based(&mut state.basic_state.gameboard, AI_TEAM);
+        if let GameMode::TurnBased(turn) = &mut game_mode {
+            turn.active = turn.teams.iter().position(|team| *team == AI_TEAM).unwrap();
+        }
+        state.basic_state.game_mode = game_mode;
+
+        let mut state = process_ai(state);
+        assert_eq!(state.basic_state.movements.contains_key(&id), true);
+        state.basic_state.movements.clear();
+        let state = process_ai(state);
+
+        assert_eq!(state.basic_state.movements.is_empty(), true);
+    }
+
+    #[test]
+    fn process_ai_capture_no_enemy_walks_up_to_capture_point() {
+        let (mut state, id) = setup_state_with_ai_unit_at(Coordinates::new(300.0, 300.0), BehaviourMode::Capture);
+        state.basic_state.gameboard.get_object_by_id_mut(0).unwrap().components.remove(ComponentKind::Health);
+        let capture_point = GameObject::new(GameObjectType::Interactable, Coordinates::new(300.0, 100.0), Size::new(20.0, 20.0))
+            .with_interaction(Interaction::CapturePoint{captured_by: None});
+        let capture_point_id = state.basic_state.gameboard.add_object(capture_point).unwrap();
+
+        let state = process_ai(state);
+
+        assert_eq!(state.basic_state.interaction_orders.get(&id), Some(&capture_point_id));
+    }
+
+    struct MoveToCorner;
+
+    impl Behaviour for MoveToCorner {
+        fn decide(&self, basic_state: &mut BasicState, id: u32, _: Option<u32>) {
+            game_managers::order_move(basic_state, &[id], &Coordinates::new(400.0, 400.0));
+        }
+    }
+
+    #[test]
+    fn process_ai_custom_registered_behaviour_decides() {
+        let (mut state, id) = setup_state_with_ai_unit_at(Coordinates::new(300.0, 300.0), BehaviourMode::Custom{name: "corner".to_string()});
+        state.ai_state.behaviours.register("corner", Rc::new(MoveToCorner));
+
+        let state = process_ai(state);
+
+        assert_eq!(state.basic_state.movements[&id].get_destination(), Some(&Coordinates::new(400.0, 400.0)));
+    }
+
+    #[test]
+    fn ai_state_load_registered_behaviours_kept() {
+        let mut ai_state = AiState::new(1);
+        ai_state.behaviours.register("corner", Rc::new(MoveToCorner));
+
+        ai_state.load(AiState::new(2));
+
+        assert_eq!(ai_state.behaviours.get("corner").is_some(), true);
+    }
+}
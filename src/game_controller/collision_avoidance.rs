@@ -0,0 +1,129 @@
+use crate::algebra_basics::{Coordinates, Size};
+use crate::algebra_basics;
+use crate::game_data::gameboard::Gameboard;
+use crate::game_data::game_object::GameObjectType;
+
+// Directions tried, relative to the desired one, when the desired step would run into another object.
+const STEERING_ANGLES: [f64; 6] = [45.0, -45.0, 90.0, -90.0, 135.0, -135.0];
+
+// Returns the position the object can safely step to, or None when every direction is blocked
+// and the object has to wait.
+pub fn steer(id: u32, current: &Coordinates, proposed: &Coordinates, size: &Size, gameboard: &Gameboard) -> Option<Coordinates> {
+    if !check_if_position_is_blocked(id, current, proposed, size, gameboard) {
+        return Some(proposed.clone());
+    }
+
+    let step_x = proposed.x - current.x;
+    let step_y = proposed.y - current.y;
+
+    for angle in STEERING_ANGLES.iter() {
+        let (sin, cos) = angle.to_radians().sin_cos();
+        let candidate = Coordinates::new(current.x + step_x * cos - step_y * sin, current.y + step_x * sin + step_y * cos);
+
+        if !check_if_position_is_blocked(id, current, &candidate, size, gameboard) {
+            return Some(candidate);
+        }
+    }
+
+    return None;
+}
+
+// Obstacles and other units block, passable objects such as open doors don't, since paths lead through them.
+// Objects the unit already overlaps don't block it, otherwise units spawned on top of each other
+// could never separate. The board edge always blocks.
+fn check_if_position_is_blocked(id: u32, current: &Coordinates, candidate: &Coordinates, size: &Size, gameboard: &Gameboard) -> bool {
+    if !gameboard.check_if_area_is_within_bounds(candidate, size) {
+        return true;
+    }
+
+    gameboard.get_objects_in_area(candidate, size).into_iter()
+        .filter(|other_id| *other_id != id)
+        .filter_map(|other_id| gameboard.get_object_by_id(other_id))
+        .filter(|other| other.is_obstacle() || other.object_type == GameObjectType::Selectable)
+        .any(|other| !algebra_basics::check_if_rectangles_overlap(current, size, &other.position, &other.size))
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use crate::game_data::game_object::GameObject;
+    use crate::game_data::components::Interaction;
+    use crate::game_data::gameboard::GameboardObjectOperation;
+
+    fn setup_gameboard_with_unit_and_obstacle(obstacle_position: Coordinates) -> Gameboard {
+        let mut gameboard = Gameboard::new();
+        gameboard.add_object(GameObject::new(GameObjectType::Selectable, Coordinates::new(0.0, 0.0), Size::new(10.0, 10.0))).unwrap();
+        gameboard.add_object(GameObject::new(GameObjectType::Selectable, obstacle_position, Size::new(10.0, 10.0))).unwrap();
+
+        return gameboard;
+    }
+
+    #[test]
+    fn steer_free_way_proposed_position_returned() {
+        let gameboard = setup_gameboard_with_unit_and_obstacle(Coordinates::new(100.0, 100.0));
+        let proposed = Coordinates::new(2.0, 0.0);
+
+        let result = steer(0, &Coordinates::new(0.0, 0.0), &proposed, &Size::new(10.0, 10.0), &gameboard);
+
+        assert_eq!(result, Some(proposed));
+    }
+
+    #[test]
+    fn steer_object_ahead_position_steered_around_it_returned() {
+        let gameboard = setup_gameboard_with_unit_and_obstacle(Coordinates::new(11.0, 5.0));
+
+        let result = steer(0, &Coordinates::new(0.0, 0.0), &Coordinates::new(2.0, 0.0), &Size::new(10.0, 10.0), &gameboard).unwrap();
+
+        assert_ne!(result, Coordinates::new(2.0, 0.0));
+        assert_eq!(algebra_basics::check_if_rectangles_overlap(&result, &Size::new(10.0, 10.0), &Coordinates::new(11.0, 5.0), &Size::new(10.0, 10.0)), false);
+    }
+
+    #[test]
+    fn steer_every_direction_blocked_returns_none() {
+        let mut gameboard = Gameboard::new();
+        gameboard.add_object(GameObject::new(GameObjectType::Selectable, Coordinates::new(20.0, 20.0), Size::new(10.0, 10.0))).unwrap();
+        for position in [(9.5, 9.5), (30.5, 9.5), (9.5, 30.5), (30.5, 30.5), (20.0, 9.5), (20.0, 30.5), (9.5, 20.0), (30.5, 20.0)].iter() {
+            gameboard.add_object(GameObject::new(GameObjectType::Static, Coordinates::new(position.0, position.1), Size::new(10.0, 10.0))).unwrap();
+        }
+
+        let result = steer(0, &Coordinates::new(20.0, 20.0), &Coordinates::new(21.0, 20.0), &Size::new(10.0, 10.0), &gameboard);
+
+        assert_eq!(result, None);
+    }
+
+    #[test]
+    fn steer_step_past_board_edge_position_within_board_returned() {
+        let gameboard = setup_gameboard_with_unit_and_obstacle(Coordinates::new(100.0, 100.0));
+
+        let result = steer(0, &Coordinates::new(0.0, 0.0), &Coordinates::new(-2.0, 0.0), &Size::new(10.0, 10.0), &gameboard).unwrap();
+
+        assert_eq!(gameboard.check_if_area_is_within_bounds(&result, &Size::new(10.0, 10.0)), true);
+    }
+
+    #[test]
+    fn steer_already_overlapping_object_doesent_block() {
+        let gameboard = setup_gameboard_with_unit_and_obstacle(Coordinates::new(5.0, 5.0));
+        let proposed = Coordinates::new(1.0, 0.0);
+
+        let result = steer(0, &Coordinates::new(0.0, 0.0), &proposed, &Size::new(10.0, 10.0), &gameboard);
+
+        assert_eq!(result, Some(proposed));
+    }
+
+    #[test]
+    fn steer_open_door_ahead_unit_walks_through() {
+        let mut gameboard = Gameboard::new();
+        gameboard.add_object(GameObject::new(GameObjectType::Selectable, Coordinates::new(0.0, 20.0), Size::new(10.0, 10.0))).unwrap();
+        let door = GameObject::new(GameObjectType::Interactable, Coordinates::new(20.0, 0.0), Size::new(10.0, 50.0));
+        gameboard.add_object(door.with_interaction(Interaction::Door{open: true})).unwrap();
+
+        let mut position = Coordinates::new(0.0, 20.0);
+        for _ in 0..20 {
+            let proposed = Coordinates::new(position.x + 2.0, position.y);
+            position = steer(0, &position, &proposed, &Size::new(10.0, 10.0), &gameboard).unwrap();
+            gameboard.execute_operation(0, GameboardObjectOperation::Move(position.clone())).unwrap();
+        }
+
+        assert_eq!(position, Coordinates::new(40.0, 20.0));
+    }
+}
@@ -0,0 +1,201 @@
+use std::collections::BTreeMap;
+
+use crate::algebra_basics;
+use crate::game_data::gameboard::Gameboard;
+use crate::game_data::components::{Health, Armor, Weapon};
+use crate::game_controller::game_managers::{BasicState, BasicStateContainer, GameEvent, UserInput};
+use crate::game_controller::turns::GameMode;
+use crate::game_controller::movement_manager::MovementHandler;
+use crate::game_controller::interactions;
+use crate::game_controller::line_of_sight;
+
+// A turn-based strike hurts as much as this many seconds of real-time fighting.
+const STRIKE_TIME: f64 = 1.0;
+// How far a chased target may get from the end of the chase path before the path is recalculated.
+const CHASE_REPATH_DISTANCE: f64 = 10.0;
+
+pub fn check_if_within_weapon_range(gameboard: &Gameboard, unit_id: u32, target_id: u32) -> bool {
+    let (unit, target) = match (gameboard.get_object_by_id(unit_id), gameboard.get_object_by_id(target_id)) {
+        (Some(unit), Some(target)) => (unit, target),
+        _ => return false
+    };
+    let weapon = match unit.components.get::<Weapon>() {
+        Some(weapon) => weapon,
+        None => return false
+    };
+
+    let distance = algebra_basics::get_distance_between_rectangles(&unit.position, &unit.size, &target.position, &target.size);
+    return distance <= weapon.range && line_of_sight::check_if_objects_see_each_other(gameboard, unit_id, target_id);
+}
+
+// Damage the target takes from the weapon over the given time, after its armor is applied.
+pub fn get_damage(weapon: &Weapon, armor: Option<&Armor>, time: f64) -> f64 {
+    let armor = armor.map(|armor| armor.value).unwrap_or(0.0);
+    return (weapon.damage - armor).max(0.0) * time;
+}
+
+// The target is removed from the game once its health runs out.
+pub fn apply_damage(basic_state: &mut BasicState, target_id: u32, damage: f64) {
+    let health = match basic_state.gameboard.get_component_mut::<Health>(target_id) {
+        Some(health) => health,
+        None => return
+    };
+    health.current -= damage;
+
+    if health.current <= 0.0 {
+        if let Err(error) = basic_state.remove_object(target_id) {
+            println!("Error: {}", error);
+            return;
+        }
+        basic_state.game_events.push(GameEvent::UnitDied(target_id));
+    }
+}
+
+// A single attack of the turn-based mode. Range and action points are checked by the caller.
+pub fn strike(basic_state: &mut BasicState, unit_id: u32, target_id: u32) {
+    let gameboard = &basic_state.gameboard;
+    let damage = match (gameboard.get_component::<Weapon>(unit_id), gameboard.get_object_by_id(target_id)) {
+        (Some(weapon), Some(target)) => get_damage(weapon, target.components.get::<Armor>(), STRIKE_TIME),
+        _ => return
+    };
+
+    apply_damage(basic_state, target_id, damage);
+}
+
+// Attackers in range stand still and hurt their target, the others chase it.
+// Only used in real time, turn-based attacks are single strikes.
+pub fn process_attack_orders<S>(mut state: S) -> S
+where S: BasicStateContainer {
+    let basic_state = state.get_basic_state();
+
+    let time = match (&basic_state.external_event, &basic_state.game_mode) {
+        (UserInput::Update(time), GameMode::RealTime) => *time,
+        _ => return state
+    };
+
+    let mut orders: Vec<(u32, u32)> = basic_state.attack_orders.iter().map(|(unit_id, target_id)| (*unit_id, *target_id)).collect();
+    orders.sort();
+
+    let mut damage_taken: BTreeMap<u32, f64> = BTreeMap::new();
+    for (unit_id, target_id) in orders {
+        let gameboard = &basic_state.gameboard;
+        let (unit, target) = match (gameboard.get_object_by_id(unit_id), gameboard.get_object_by_id(target_id)) {
+            (Some(unit), Some(target)) => (unit, target),
+            _ => {
+                basic_state.attack_orders.remove(&unit_id);
+                continue;
+            }
+        };
+        let weapon = match unit.components.get::<Weapon>() {
+            Some(weapon) => weapon,
+            None => {
+                basic_state.attack_orders.remove(&unit_id);
+                continue;
+            }
+        };
+
+        if check_if_within_weapon_range(gameboard, unit_id, target_id) {
+            basic_state.movements.remove(&unit_id);
+            *damage_taken.entry(target_id).or_insert(0.0) += get_damage(weapon, target.components.get::<Armor>(), time);
+            continue;
+        }
+
+        let adjacent = interactions::get_adjacent_position(&unit.position, &unit.size, target);
+        let adjacent = gameboard.clamp_position(&adjacent, &unit.size);
+        let needs_path = match basic_state.movements.get(&unit_id).and_then(|movement| movement.get_destination()) {
+            Some(destination) => algebra_basics::get_distance(destination, &adjacent) > CHASE_REPATH_DISTANCE,
+            None => true
+        };
+
+        if needs_path {
+            let start = unit.position.clone();
+            let path = basic_state.pathfinder.find_path(&start, &adjacent, &unit.size, gameboard);
+            basic_state.movements.insert(unit_id, MovementHandler::start_with_path(start, path));
+        }
+    }
+
+    for (target_id, damage) in damage_taken {
+        apply_damage(basic_state, target_id, damage);
+    }
+
+    return state;
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use crate::testing::setup;
+    use crate::algebra_basics::{Coordinates, Size};
+    use crate::game_data::components::Team;
+    use crate::game_data::game_object::{GameObject, GameObjectType};
+
+    // Puts an enemy with the given health at the position, the local unit 0 gets a weapon.
+    fn setup_state_with_enemy_at(position: Coordinates, health: f64) -> (setup::MockMainState, u32) {
+        let mut state = setup::setup_game_state_with_one_object();
+        let gameboard = &mut state.basic_state.gameboard;
+        gameboard.get_object_by_id_mut(0).unwrap().components.insert(Weapon{damage: 10.0, range: 20.0});
+
+        let mut enemy = setup::setup_selectable_object_at(position);
+        enemy.components.insert(Team{id: setup::LOCAL_TEAM + 1});
+        enemy.components.insert(Health{current: health, max: health});
+        let enemy_id = gameboard.add_object(enemy).unwrap();
+        state.basic_state.attack_orders.insert(0, enemy_id);
+        state.basic_state.external_event = UserInput::Update(0.5);
+
+        (state, enemy_id)
+    }
+
+    #[test]
+    fn get_damage_armored_target_damage_reduced_by_armor() {
+        let weapon = Weapon{damage: 10.0, range: 20.0};
+
+        assert_eq!(get_damage(&weapon, Some(&Armor{value: 4.0}), 2.0), 12.0);
+        assert_eq!(get_damage(&weapon, Some(&Armor{value: 15.0}), 2.0), 0.0);
+    }
+
+    #[test]
+    fn process_attack_orders_target_in_range_loses_health() {
+        let (state, enemy_id) = setup_state_with_enemy_at(Coordinates::new(60.0, 0.0), 100.0);
+
+        let state = process_attack_orders(state);
+
+        let health = state.basic_state.gameboard.get_component::<Health>(enemy_id).unwrap();
+        assert_eq!(health.current, 95.0);
+    }
+
+    #[test]
+    fn process_attack_orders_health_runs_out_unit_removed_with_its_orders() {
+        let (mut state, enemy_id) = setup_state_with_enemy_at(Coordinates::new(60.0, 0.0), 5.0);
+        state.basic_state.selected_ids.insert(enemy_id);
+        state.basic_state.movements.insert(enemy_id, MovementHandler::start(Coordinates::new(60.0, 0.0), Coordinates::new(200.0, 0.0)));
+
+        let state = process_attack_orders(state);
+
+        assert_eq!(state.basic_state.gameboard.get_object_by_id(enemy_id).is_none(), true);
+        assert_eq!(state.basic_state.game_events, vec![GameEvent::UnitDied(enemy_id)]);
+        assert_eq!(state.basic_state.selected_ids.contains(&enemy_id), false);
+        assert_eq!(state.basic_state.movements.contains_key(&enemy_id), false);
+        assert_eq!(state.basic_state.attack_orders.is_empty(), true);
+    }
+
+    #[test]
+    fn process_attack_orders_target_out_of_range_attacker_chases_it() {
+        let (state, enemy_id) = setup_state_with_enemy_at(Coordinates::new(300.0, 0.0), 100.0);
+
+        let state = process_attack_orders(state);
+
+        assert_eq!(state.basic_state.movements.contains_key(&0), true);
+        assert_eq!(state.basic_state.gameboard.get_component::<Health>(enemy_id).unwrap().current, 100.0);
+    }
+
+    #[test]
+    fn process_attack_orders_wall_between_units_target_not_hurt() {
+        let (mut state, enemy_id) = setup_state_with_enemy_at(Coordinates::new(60.0, 0.0), 100.0);
+        let wall = GameObject::new(GameObjectType::Static, Coordinates::new(52.0, 0.0), Size::new(6.0, 50.0));
+        state.basic_state.gameboard.add_object(wall).unwrap();
+
+        let state = process_attack_orders(state);
+
+        assert_eq!(state.basic_state.gameboard.get_component::<Health>(enemy_id).unwrap().current, 100.0);
+    }
+}
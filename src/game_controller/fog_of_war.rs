@@ -0,0 +1,132 @@
+use std::collections::BTreeMap;
+
+use crate::algebra_basics::{Coordinates, Size};
+use crate::algebra_basics;
+use crate::game_data::gameboard::Gameboard;
+use crate::game_data::components::{ComponentKind, Sight};
+use crate::game_data::visibility::VisibilityMap;
+use crate::game_controller::game_managers::{BasicStateContainer, UserInput};
+use crate::game_controller::line_of_sight;
+
+// Recalculates what every team with at least one sighted unit sees. Teams without such units
+// get no visibility map and see the whole board.
+pub fn update_visibility(gameboard: &mut Gameboard) {
+    let mut sighted_units: BTreeMap<u32, Vec<u32>> = BTreeMap::new();
+    for id in gameboard.get_objects_with_components(&[ComponentKind::Team, ComponentKind::Sight]) {
+        let team = gameboard.get_team(id).unwrap();
+        sighted_units.entry(team).or_default().push(id);
+    }
+
+    for (team, unit_ids) in sighted_units {
+        let mut map = gameboard.get_visibility(team)
+            .cloned()
+            .unwrap_or_else(|| VisibilityMap::new(gameboard.get_bounds()));
+        map.hide_visible();
+
+        for id in unit_ids {
+            reveal_around(gameboard, &mut map, id);
+        }
+
+        gameboard.set_visibility(team, map);
+    }
+}
+
+// Reveals the cells within the sight radius of the unit which aren't hidden behind opaque objects.
+fn reveal_around(gameboard: &Gameboard, map: &mut VisibilityMap, id: u32) {
+    let (object, radius) = match (gameboard.get_object_by_id(id), gameboard.get_component::<Sight>(id)) {
+        (Some(object), Some(sight)) => (object, sight.radius),
+        _ => return
+    };
+
+    let eye = algebra_basics::get_middle(&object.position, &object.size);
+    let reach_position = Coordinates::new(eye.x - radius, eye.y - radius);
+    let reach_size = Size::new(2.0 * radius, 2.0 * radius);
+    let (min, max) = map.get_cell_range(&reach_position, &reach_size);
+
+    for row in min.1..=max.1 {
+        for column in min.0..=max.0 {
+            let center = VisibilityMap::get_cell_center(column, row);
+            if algebra_basics::get_distance(&eye, &center) > radius {
+                continue;
+            }
+
+            if line_of_sight::line_of_sight(&eye, &center, gameboard).is_clear() {
+                map.reveal(column, row);
+            }
+        }
+    }
+}
+
+pub fn process_visibility<S>(mut state: S) -> S
+where S: BasicStateContainer {
+    let basic_state = state.get_basic_state();
+
+    if let UserInput::Update(_) = basic_state.external_event {
+        update_visibility(&mut basic_state.gameboard);
+    }
+
+    return state;
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use crate::testing::setup;
+    use crate::game_data::components::Team;
+    use crate::game_data::gameboard::GameboardObjectOperation;
+    use crate::game_data::visibility::CellVisibility;
+    use crate::game_data::game_object::{GameObject, GameObjectType};
+
+    // Local unit 0 with a sight radius of 100 and an enemy at the position.
+    fn setup_gameboard_with_enemy_at(position: Coordinates) -> (Gameboard, u32) {
+        let mut gameboard = setup::setup_gameboard_with_selectable_object();
+        gameboard.get_object_by_id_mut(0).unwrap().components.insert(Sight{radius: 100.0});
+
+        let mut enemy = setup::setup_selectable_object_at(position);
+        enemy.components.insert(Team{id: setup::LOCAL_TEAM + 1});
+        let enemy_id = gameboard.add_object(enemy).unwrap();
+        (gameboard, enemy_id)
+    }
+
+    #[test]
+    fn update_visibility_enemy_within_sight_radius_visible() {
+        let (mut gameboard, enemy_id) = setup_gameboard_with_enemy_at(Coordinates::new(100.0, 0.0));
+
+        update_visibility(&mut gameboard);
+
+        assert_eq!(gameboard.check_if_visible_to(setup::LOCAL_TEAM, enemy_id), true);
+    }
+
+    #[test]
+    fn update_visibility_enemy_beyond_sight_radius_hidden() {
+        let (mut gameboard, enemy_id) = setup_gameboard_with_enemy_at(Coordinates::new(300.0, 300.0));
+
+        update_visibility(&mut gameboard);
+
+        assert_eq!(gameboard.check_if_visible_to(setup::LOCAL_TEAM, enemy_id), false);
+        assert_eq!(gameboard.get_objects_visible_to(setup::LOCAL_TEAM), vec![0]);
+    }
+
+    #[test]
+    fn update_visibility_enemy_behind_wall_hidden() {
+        let (mut gameboard, enemy_id) = setup_gameboard_with_enemy_at(Coordinates::new(100.0, 0.0));
+        let wall = GameObject::new(GameObjectType::Static, Coordinates::new(60.0, 0.0), Size::new(20.0, 200.0));
+        gameboard.add_object(wall).unwrap();
+
+        update_visibility(&mut gameboard);
+
+        assert_eq!(gameboard.check_if_visible_to(setup::LOCAL_TEAM, enemy_id), false);
+    }
+
+    #[test]
+    fn update_visibility_unit_walked_away_area_stays_explored() {
+        let (mut gameboard, _) = setup_gameboard_with_enemy_at(Coordinates::new(400.0, 400.0));
+        update_visibility(&mut gameboard);
+        gameboard.execute_operation(0, GameboardObjectOperation::Move(Coordinates::new(300.0, 0.0))).unwrap();
+
+        update_visibility(&mut gameboard);
+
+        let map = gameboard.get_visibility(setup::LOCAL_TEAM).unwrap();
+        assert_eq!(map.get(0, 0), CellVisibility::Explored);
+    }
+}
@@ -0,0 +1,204 @@
+use serde::{Serialize, Deserialize};
+
+use crate::algebra_basics::{Coordinates, Size, Vector};
+use crate::algebra_basics;
+
+// Free space left between neighbouring units in a formation.
+const FORMATION_GAP: f64 = 10.0;
+
+#[derive(PartialEq, Debug, Clone, Copy, Serialize, Deserialize)]
+pub enum Formation {
+    Line,
+    Column,
+    Box,
+    Wedge
+}
+
+// Slot offsets in spacing units, with x pointing to the right of the group and y to its back.
+fn get_local_offsets(formation: Formation, count: usize) -> Vec<(f64, f64)> {
+    let middle = (count as f64 - 1.0) / 2.0;
+
+    match formation {
+        Formation::Line => (0..count).map(|i| (i as f64 - middle, 0.0)).collect(),
+        Formation::Column => (0..count).map(|i| (0.0, i as f64 - middle)).collect(),
+        Formation::Box => {
+            let columns = (count as f64).sqrt().ceil().max(1.0) as usize;
+            let rows = count.div_ceil(columns);
+            let middle_column = (columns as f64 - 1.0) / 2.0;
+            let middle_row = (rows as f64 - 1.0) / 2.0;
+
+            (0..count).map(|i| ((i % columns) as f64 - middle_column, (i / columns) as f64 - middle_row)).collect()
+        },
+        Formation::Wedge => {
+            (0..count).map(|i| {
+                let row = i.div_ceil(2) as f64;
+                let side = if i % 2 == 1 { -1.0 } else { 1.0 };
+                (side * row, row)
+            }).collect()
+        }
+    }
+}
+
+// Positions for each unit of the group, so that they don't stack when sent to the same destination.
+// The formation faces from the group's current middle toward the destination and is centred on it,
+// except for the wedge whose tip sits on the destination.
+pub fn get_formation_slots(formation: Formation, current_positions: &[&Coordinates], sizes: &[&Size], destination: &Coordinates) -> Vec<Coordinates> {
+    let count = current_positions.len();
+    if count == 0 {
+        return Vec::new();
+    }
+
+    let (right, back) = get_formation_axes(current_positions, destination);
+    let right_spacing = get_extent_along(sizes, right) + FORMATION_GAP;
+    let back_spacing = get_extent_along(sizes, back) + FORMATION_GAP;
+
+    let slots: Vec<Coordinates> = get_local_offsets(formation, count)
+        .into_iter()
+        .map(|(x, y)| Coordinates::new(
+            destination.x + x * right_spacing * right.0 + y * back_spacing * back.0,
+            destination.y + x * right_spacing * right.1 + y * back_spacing * back.1))
+        .collect();
+
+    return assign_slots(current_positions, slots);
+}
+
+fn get_formation_axes(current_positions: &[&Coordinates], destination: &Coordinates) -> ((f64, f64), (f64, f64)) {
+    let count = current_positions.len() as f64;
+    let middle = Coordinates::new(
+        current_positions.iter().map(|position| position.x).sum::<f64>() / count,
+        current_positions.iter().map(|position| position.y).sum::<f64>() / count);
+
+    // Facing up when the group is already standing on the destination.
+    let mut facing = (0.0, -1.0);
+    if algebra_basics::get_distance(&middle, destination) > 0.0 {
+        let vector = Vector::to_unit_vector(&Vector::get_vector(&middle, destination));
+        facing = (vector.x, vector.y);
+    }
+
+    let right = (-facing.1, facing.0);
+    let back = (-facing.0, -facing.1);
+    (right, back)
+}
+
+// Length of the widest unit measured along the axis, so that units on diagonal headings don't overlap.
+fn get_extent_along(sizes: &[&Size], axis: (f64, f64)) -> f64 {
+    sizes.iter()
+        .map(|size| (size.width * axis.0).abs() + (size.height * axis.1).abs())
+        .fold(0.0, f64::max)
+}
+
+// Greedily hands every slot, front ones first, to the closest unit which doesn't have one yet.
+fn assign_slots(current_positions: &[&Coordinates], slots: Vec<Coordinates>) -> Vec<Coordinates> {
+    let mut assigned: Vec<Option<Coordinates>> = vec![None; current_positions.len()];
+
+    for slot in slots {
+        let closest = (0..current_positions.len())
+            .filter(|i| assigned[*i].is_none())
+            .min_by(|a, b| {
+                let distance_a = algebra_basics::get_distance(current_positions[*a], &slot);
+                let distance_b = algebra_basics::get_distance(current_positions[*b], &slot);
+                distance_a.partial_cmp(&distance_b).unwrap()
+            });
+
+        if let Some(closest) = closest {
+            assigned[closest] = Some(slot);
+        }
+    }
+
+    assigned.into_iter().map(|slot| slot.unwrap()).collect()
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    fn assert_slots_dont_overlap(slots: &[Coordinates], size: &Size) {
+        for (i, a) in slots.iter().enumerate() {
+            for b in &slots[i + 1..] {
+                assert_eq!(algebra_basics::check_if_rectangles_overlap(a, size, b, size), false);
+            }
+        }
+    }
+
+    #[test]
+    fn get_formation_slots_single_unit_destination_returned() {
+        let position = Coordinates::new(0.0, 0.0);
+        let size = Size::new(50.0, 50.0);
+        let destination = Coordinates::new(200.0, 100.0);
+
+        let slots = get_formation_slots(Formation::Wedge, &[&position], &[&size], &destination);
+
+        assert_eq!(slots, vec![destination]);
+    }
+
+    #[test]
+    fn get_formation_slots_line_slots_are_perpendicular_to_movement() {
+        let a = Coordinates::new(0.0, 0.0);
+        let b = Coordinates::new(0.0, 100.0);
+        let size = Size::new(20.0, 20.0);
+        let destination = Coordinates::new(300.0, 50.0);
+
+        let slots = get_formation_slots(Formation::Line, &[&a, &b], &[&size, &size], &destination);
+
+        assert_eq!(slots, vec![Coordinates::new(300.0, 35.0), Coordinates::new(300.0, 65.0)]);
+    }
+
+    #[test]
+    fn get_formation_slots_column_slots_are_along_movement() {
+        let a = Coordinates::new(0.0, 0.0);
+        let b = Coordinates::new(0.0, 10.0);
+        let size = Size::new(20.0, 20.0);
+        let destination = Coordinates::new(0.0, 300.0);
+
+        let slots = get_formation_slots(Formation::Column, &[&a, &b], &[&size, &size], &destination);
+
+        assert_eq!(slots[0].x, slots[1].x);
+        assert_eq!((slots[0].y - slots[1].y).abs(), 30.0);
+    }
+
+    #[test]
+    fn get_formation_slots_box_and_wedge_slots_dont_overlap() {
+        let positions = [Coordinates::new(0.0, 0.0), Coordinates::new(30.0, 0.0), Coordinates::new(60.0, 0.0), Coordinates::new(90.0, 0.0), Coordinates::new(120.0, 0.0)];
+        let position_refs: Vec<&Coordinates> = positions.iter().collect();
+        let size = Size::new(25.0, 40.0);
+        let sizes = vec![&size; 5];
+        let destination = Coordinates::new(200.0, 300.0);
+
+        for formation in [Formation::Box, Formation::Wedge].iter() {
+            let slots = get_formation_slots(*formation, &position_refs, &sizes, &destination);
+
+            assert_eq!(slots.len(), 5);
+            assert_slots_dont_overlap(&slots, &size);
+        }
+    }
+
+    #[test]
+    fn get_formation_slots_diagonal_heading_slots_dont_overlap() {
+        let positions = [Coordinates::new(0.0, 0.0), Coordinates::new(60.0, 0.0), Coordinates::new(120.0, 0.0), Coordinates::new(0.0, 60.0), Coordinates::new(60.0, 60.0), Coordinates::new(120.0, 60.0)];
+        let position_refs: Vec<&Coordinates> = positions.iter().collect();
+        let size = Size::new(50.0, 50.0);
+        let sizes = vec![&size; 6];
+        // The group's middle is at (60, 30), so the destination lies at 45 degrees from it.
+        let destination = Coordinates::new(360.0, 330.0);
+
+        for formation in [Formation::Line, Formation::Column, Formation::Box, Formation::Wedge].iter() {
+            let slots = get_formation_slots(*formation, &position_refs, &sizes, &destination);
+
+            assert_slots_dont_overlap(&slots, &size);
+        }
+    }
+
+    #[test]
+    fn get_formation_slots_wedge_leader_slot_is_destination() {
+        let a = Coordinates::new(0.0, 0.0);
+        let b = Coordinates::new(10.0, 0.0);
+        let c = Coordinates::new(20.0, 0.0);
+        let size = Size::new(20.0, 20.0);
+        let destination = Coordinates::new(10.0, 300.0);
+
+        let slots = get_formation_slots(Formation::Wedge, &[&a, &b, &c], &[&size, &size, &size], &destination);
+
+        assert_eq!(slots.contains(&destination), true);
+        assert_eq!(slots.iter().all(|slot| slot.y <= destination.y), true);
+    }
+}
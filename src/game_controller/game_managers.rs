@@ -1,16 +1,40 @@
-use std::collections::HashMap;
+use std::collections::{HashMap, BTreeSet};
 
 use crate::algebra_basics::{Coordinates, Size};
-use crate::game_data::gameboard::{Gameboard, GameboardObjectOperation};
-use crate::game_data::game_object::GameObject;
-use crate::game_data::gameboard;
+use crate::algebra_basics;
+use crate::game_data::gameboard::{Gameboard, GameboardObjectOperation, GameboardError};
+use crate::game_data::components::{Health, Weapon};
+use crate::game_data::game_object::{GameObject, GameObjectType};
 use crate::game_controller::movement_manager::MovementHandler;
+use crate::game_controller::pathfinder::Pathfinder;
+use crate::game_controller::collision_avoidance;
+use crate::game_controller::formations;
+use crate::game_controller::formations::Formation;
+use crate::game_controller::interactions;
+use crate::game_controller::combat;
+use crate::game_controller::turns;
+use crate::game_controller::turns::GameMode;
+use crate::game_controller::order_queues::OrderQueue;
+use crate::game_controller::rules::Rules;
+
+// Updates in a row without getting any closer after which a moving object stops.
+const MAX_STALLED_UPDATES: u32 = 60;
 
 #[derive(PartialEq, Debug)]
 pub enum UserInput {
     NoInputCursorPos(Coordinates),
     LeftMouse(Coordinates),
+    ShiftLeftMouse(Coordinates),
     RightMouse(Coordinates),
+    ShiftRightMouse(Coordinates),
+    SelectionBox{start: Coordinates, end: Coordinates, add: bool},
+    SelectFormation(Formation),
+    QuickSave,
+    QuickLoad,
+    Update(f64), // seconds since the previous update
+    EndTurn,
+    Patrol,
+    Stop,
     D,
     C
 }
@@ -20,59 +44,292 @@ impl UserInput {
         match self {
             UserInput::NoInputCursorPos(coordinates) => return Some(coordinates),
             UserInput::LeftMouse(coordinates) => return Some(coordinates),
+            UserInput::ShiftLeftMouse(coordinates) => return Some(coordinates),
             UserInput::RightMouse(coordinates) => return Some(coordinates),
+            UserInput::ShiftRightMouse(coordinates) => return Some(coordinates),
+            UserInput::SelectionBox{end, ..} => return Some(end),
             _ => return None
         }
     }
 }
 
+#[derive(PartialEq, Debug)]
+pub enum GameEvent {
+    MovementFinished(u32), // the object reached its destination or gave up on it
+    Interaction{unit_id: u32, target_id: u32},
+    UnitDied(u32),
+    TurnStarted{team: u32, number: u32},
+    GameOver{winner: u32}
+}
+
 pub struct BasicState {
-    pub current_selected_id: u32,
+    pub selected_ids: BTreeSet<u32>,
     pub external_event: UserInput,
     pub gameboard: Gameboard,
-    pub movements: HashMap<u32, MovementHandler>
+    pub movements: HashMap<u32, MovementHandler>,
+    pub interaction_orders: HashMap<u32, u32>, // unit id to the id of the object it walks up to
+    pub attack_orders: HashMap<u32, u32>, // unit id to the id of the unit it attacks
+    pub order_queues: HashMap<u32, OrderQueue>,
+    pub local_team: u32, // team of the player at this machine
+    pub game_mode: GameMode,
+    pub rules: Rules,
+    pub formation: Formation,
+    pub pathfinder: Pathfinder,
+    pub game_events: Vec<GameEvent> // emitted during the current iteration of the game loop
+}
+
+impl BasicState {
+    // Nothing may keep referring to a removed object, otherwise later orders would look it up.
+    pub fn remove_object(&mut self, id: u32) -> Result<(), GameboardError> {
+        self.gameboard.remove_object(id)?;
+        self.selected_ids.remove(&id);
+        self.movements.remove(&id);
+        self.interaction_orders.remove(&id);
+        self.interaction_orders.retain(|_, target_id| *target_id != id);
+        self.attack_orders.remove(&id);
+        self.attack_orders.retain(|_, target_id| *target_id != id);
+        self.order_queues.remove(&id);
+
+        return Ok(());
+    }
 }
 
 pub trait BasicStateContainer {
     fn get_basic_state(&mut self) -> &mut BasicState;
 }
 
+pub fn clear_game_events<S>(mut state: S) -> S
+where S: BasicStateContainer {
+    state.get_basic_state().game_events.clear();
+    return state;
+}
+
 pub fn process_selection<S>(mut state: S) -> S
 where S: BasicStateContainer {
     let basic_state = state.get_basic_state();
+    let selected_ids = &mut basic_state.selected_ids;
+    let local_team = basic_state.local_team;
+
+    match &basic_state.external_event {
+        UserInput::LeftMouse(position) => {
+            if let Some(id) = get_object_id_at(&basic_state.gameboard, position, local_team) {
+                selected_ids.clear();
+                selected_ids.insert(id);
+            }
+        },
+        UserInput::ShiftLeftMouse(position) => {
+            if let Some(id) = get_object_id_at(&basic_state.gameboard, position, local_team) {
+                if !selected_ids.remove(&id) {
+                    selected_ids.insert(id);
+                }
+            }
+        },
+        UserInput::SelectionBox{start, end, add} => {
+            let (box_position, box_size) = algebra_basics::get_rectangle_from_corners(start, end);
+
+            if !add {
+                selected_ids.clear();
+            }
 
-    let position;
-    if let UserInput::LeftMouse(pos) = &basic_state.external_event {
-        position = pos
+            let gameboard = &basic_state.gameboard;
+            selected_ids.extend(gameboard.get_objects_in_area(&box_position, &box_size)
+                .into_iter()
+                .filter(|id| check_if_object_is_controllable(gameboard, *id, local_team)));
+        },
+        _ => {}
     }
-    else {
+
+    return state;
+}
+
+fn get_object_id_at(gameboard: &Gameboard, position: &Coordinates, local_team: u32) -> Option<u32> {
+    return gameboard.get_objects_at(position)
+        .into_iter()
+        .find(|id| check_if_object_is_controllable(gameboard, *id, local_team));
+}
+
+fn get_object_id_of_type_at(gameboard: &Gameboard, position: &Coordinates, object_type: GameObjectType) -> Option<u32> {
+    return gameboard.get_objects_at(position)
+        .into_iter()
+        .find(|id| check_if_object_is_of_type(gameboard, *id, &object_type));
+}
+
+fn check_if_object_is_of_type(gameboard: &Gameboard, id: u32, object_type: &GameObjectType) -> bool {
+    return gameboard.get_object_by_id(id).is_some_and(|object| object.object_type == *object_type);
+}
+
+// Units of other teams which can be hurt. Enemies hidden in the fog of war can't be targeted.
+fn get_enemy_id_at(gameboard: &Gameboard, position: &Coordinates, local_team: u32) -> Option<u32> {
+    return gameboard.get_objects_at(position)
+        .into_iter()
+        .find(|id| gameboard.get_team(*id).is_some_and(|team| team != local_team)
+            && gameboard.get_component::<Health>(*id).is_some()
+            && gameboard.check_if_visible_to(local_team, *id));
+}
+
+// The player can only select and give orders to their own units.
+fn check_if_object_is_controllable(gameboard: &Gameboard, id: u32, local_team: u32) -> bool {
+    return check_if_object_is_of_type(gameboard, id, &GameObjectType::Selectable)
+        && gameboard.get_team(id) == Some(local_team);
+}
+
+pub fn process_player_movement<S>(mut state: S) -> S
+where S: BasicStateContainer {
+    let basic_state = state.get_basic_state();
+
+    let destination = match &basic_state.external_event {
+        UserInput::RightMouse(destination) => destination.clone(),
+        _ => return state
+    };
+
+    // In the turn-based mode orders are only taken during the turn of the player.
+    if !basic_state.game_mode.check_if_team_can_act(basic_state.local_team) {
         return state;
     }
 
+    let selected_ids = get_controllable_selected_ids(basic_state);
+
+    if let Some(target_id) = get_enemy_id_at(&basic_state.gameboard, &destination, basic_state.local_team) {
+        order_attack(basic_state, &selected_ids, target_id);
+        return state;
+    }
+
+    if let Some(target_id) = get_object_id_of_type_at(&basic_state.gameboard, &destination, GameObjectType::Interactable) {
+        order_interact(basic_state, &selected_ids, target_id);
+        return state;
+    }
+
+    order_move(basic_state, &selected_ids, &destination);
+    return state;
+}
+
+// Orders are shared by the player and the computer opponent, callers make sure they command their own units.
+// Every order replaces whatever the units were doing before, queued waypoints included.
+pub fn order_move(basic_state: &mut BasicState, ids: &[u32], destination: &Coordinates) {
+    for (id, movement) in get_formation_movements(basic_state, ids, destination) {
+        start_movement(basic_state, id, movement);
+        basic_state.interaction_orders.remove(&id);
+        basic_state.attack_orders.remove(&id);
+        basic_state.order_queues.remove(&id);
+    }
+}
+
+pub fn order_interact(basic_state: &mut BasicState, ids: &[u32], target_id: u32) {
+    for (id, movement) in get_interaction_movements(basic_state, ids, target_id) {
+        start_movement(basic_state, id, movement);
+        basic_state.interaction_orders.insert(id, target_id);
+        basic_state.attack_orders.remove(&id);
+        basic_state.order_queues.remove(&id);
+    }
+}
+
+pub fn order_stop(basic_state: &mut BasicState, ids: &[u32]) {
+    for id in ids {
+        cancel_movement(basic_state, *id);
+        basic_state.interaction_orders.remove(id);
+        basic_state.attack_orders.remove(id);
+        basic_state.order_queues.remove(id);
+    }
+}
+
+// Units without a weapon can't take part in an attack.
+pub fn order_attack(basic_state: &mut BasicState, ids: &[u32], target_id: u32) {
+    let armed_ids: Vec<u32> = ids.iter()
+        .filter(|id| basic_state.gameboard.get_component::<Weapon>(**id).is_some())
+        .cloned()
+        .collect();
+
+    for id in armed_ids {
+        basic_state.interaction_orders.remove(&id);
+        basic_state.order_queues.remove(&id);
+
+        // Turn-based attacks hit right away, units out of range don't attack at all.
+        if let GameMode::TurnBased(_) = basic_state.game_mode {
+            if combat::check_if_within_weapon_range(&basic_state.gameboard, id, target_id)
+                && turns::spend_action_points(&mut basic_state.gameboard, id, turns::ATTACK_COST) {
+                combat::strike(basic_state, id, target_id);
+            }
+            continue;
+        }
+
+        basic_state.attack_orders.insert(id, target_id);
+    }
+}
+
+// Selected units the player can give orders to, in ascending order.
+pub fn get_controllable_selected_ids(basic_state: &BasicState) -> Vec<u32> {
+    return basic_state.selected_ids.iter()
+        .filter(|id| check_if_object_is_controllable(&basic_state.gameboard, **id, basic_state.local_team))
+        .cloned()
+        .collect();
+}
+
+// Paths of the units to their slots of the current formation around the destination.
+pub fn get_formation_movements(basic_state: &BasicState, ids: &[u32], destination: &Coordinates) -> Vec<(u32, MovementHandler)> {
     let gameboard = &basic_state.gameboard;
+    let units: Vec<(u32, &GameObject)> = ids.iter()
+        .filter_map(|id| gameboard.get_object_by_id(*id).map(|object| (*id, object)))
+        .collect();
+
+    let positions: Vec<&Coordinates> = units.iter().map(|(_, object)| &object.position).collect();
+    let sizes: Vec<&Size> = units.iter().map(|(_, object)| &object.size).collect();
+    let slots = formations::get_formation_slots(basic_state.formation, &positions, &sizes, destination);
+
+    return units.iter().zip(slots)
+        .map(|((id, object), slot)| {
+            let start = object.position.clone();
+            let slot = gameboard.clamp_position(&slot, &object.size);
+            let path = basic_state.pathfinder.find_path(&start, &slot, &object.size, gameboard);
+            (*id, MovementHandler::start_with_path(start, path))
+        })
+        .collect();
+}
 
-    let querry = |object_data: &(&u32, &GameObject)| {
-        gameboard::check_if_object_area_contains_coordinates(object_data.1, position)
+// Paths of the units to the spots right next to the target.
+fn get_interaction_movements(basic_state: &BasicState, ids: &[u32], target_id: u32) -> Vec<(u32, MovementHandler)> {
+    let gameboard = &basic_state.gameboard;
+    let target = match gameboard.get_object_by_id(target_id) {
+        Some(target) => target,
+        None => return Vec::new()
     };
 
-    let object = gameboard.querry_object(querry);
-    
-    if let Some(object) = object {
-        basic_state.current_selected_id = *object.0;
+    return ids.iter()
+        .filter_map(|id| gameboard.get_object_by_id(*id).map(|object| (*id, object)))
+        .map(|(id, object)| {
+            let start = object.position.clone();
+            let adjacent = interactions::get_adjacent_position(&start, &object.size, target);
+            let adjacent = gameboard.clamp_position(&adjacent, &object.size);
+            let path = basic_state.pathfinder.find_path(&start, &adjacent, &object.size, gameboard);
+            (id, MovementHandler::start_with_path(start, path))
+        })
+        .collect();
+}
+
+// In the turn-based mode the path is cut to what the unit can afford, and the points are spent right away.
+pub fn start_movement(basic_state: &mut BasicState, id: u32, mut movement: MovementHandler) {
+    cancel_movement(basic_state, id);
+    if let GameMode::TurnBased(_) = basic_state.game_mode {
+        turns::spend_on_movement(&mut basic_state.gameboard, id, &mut movement);
     }
 
-    return state;
+    basic_state.movements.insert(id, movement);
 }
 
-pub fn process_player_movement<S>(mut state: S) -> S
+// The points spent on the part of the path that wasn't walked are given back.
+pub fn cancel_movement(basic_state: &mut BasicState, id: u32) {
+    if let Some(movement) = basic_state.movements.remove(&id) {
+        if let GameMode::TurnBased(_) = basic_state.game_mode {
+            turns::refund_movement(&mut basic_state.gameboard, id, &movement);
+        }
+    }
+}
+
+pub fn process_formation_selection<S>(mut state: S) -> S
 where S: BasicStateContainer {
-    let mut basic_state = state.get_basic_state();
+    let basic_state = state.get_basic_state();
 
-    if let UserInput::RightMouse(destination) = &basic_state.external_event {
-        let selected = basic_state.gameboard.get_object_by_id(basic_state.current_selected_id).unwrap();
-        let start = selected.position.clone();
-        let movement_manager = MovementHandler::start(start, destination.clone());
-        basic_state.movements.insert(basic_state.current_selected_id, movement_manager);
+    if let UserInput::SelectFormation(formation) = basic_state.external_event {
+        basic_state.formation = formation;
     }
 
     return state;
@@ -81,25 +338,67 @@ where S: BasicStateContainer {
 pub fn proces_movement<S>(mut state: S) -> S
 where S: BasicStateContainer {
     let basic_state = state.get_basic_state();
-    let ids_to_remove = proces_movement_apply(basic_state);
+
+    let delta_time;
+    if let UserInput::Update(dt) = basic_state.external_event {
+        delta_time = dt;
+    }
+    else {
+        return state;
+    }
+
+    let ids_to_remove = proces_movement_apply(basic_state, delta_time);
 
     for id in ids_to_remove {
-        basic_state.movements.remove(&id);
+        cancel_movement(basic_state, id);
     }
 
     return state;
 }
 
-fn proces_movement_apply(state: &mut BasicState) -> Vec<u32> {
+// Objects are moved one by one in id order, each checked against the positions the others
+// already took this tick, so two moving objects never step into the same place.
+fn proces_movement_apply(state: &mut BasicState, delta_time: f64) -> Vec<u32> {
     let mut ids_to_remove = Vec::new();
 
-    let movements = &mut state.movements;
-    for (id, movement_handler) in movements {
-        if let Some(position) = movement_handler.poll_movement() {
-            state.gameboard.execute_operation(*id, GameboardObjectOperation::Move(position));
+    let mut ids: Vec<u32> = state.movements.keys().cloned().collect();
+    ids.sort();
+
+    for id in ids {
+        let movement_handler = state.movements.get_mut(&id).unwrap();
+        let object = match state.gameboard.get_object_by_id(id) {
+            Some(object) => object,
+            None => {
+                ids_to_remove.push(id);
+                continue;
+            }
+        };
+
+        let mut next_movement = movement_handler.clone();
+        if let Some(proposed) = next_movement.poll_movement(object.get_speed() * delta_time) {
+            // Waypoints past the board edge are followed along the edge instead.
+            let proposed = state.gameboard.clamp_position(&proposed, &object.size);
+            if let Some(position) = collision_avoidance::steer(id, &object.position, &proposed, &object.size, &state.gameboard) {
+                if position == proposed {
+                    *movement_handler = next_movement;
+                }
+                else {
+                    movement_handler.set_position(position.clone());
+                }
+
+                if let Err(error) = state.gameboard.execute_operation(id, GameboardObjectOperation::Move(position)) {
+                    println!("Error: {}", error);
+                    ids_to_remove.push(id);
+                    continue;
+                }
+            }
         }
-        else {
-            ids_to_remove.push(*id);
+        movement_handler.record_progress();
+
+        // Objects boxed in or pushing against each other give up instead of waiting forever.
+        if movement_handler.is_reached() || movement_handler.get_stalled_updates() >= MAX_STALLED_UPDATES {
+            ids_to_remove.push(id);
+            state.game_events.push(GameEvent::MovementFinished(id));
         }
     }
 
@@ -111,6 +410,8 @@ mod tests {
     use super::*;
     use crate::testing::setup;
     use crate::game_data::game_object::{GameObject, GameObjectType};
+    use crate::game_data::components::{Team, Health, Weapon, ActionPoints, Speed};
+    use crate::game_data::visibility::VisibilityMap;
     use crate::algebra_basics::{Coordinates, Size};
 
     #[test]
@@ -123,18 +424,150 @@ mod tests {
         assert_eq!(movement_handlers.len(), 1);
     }
 
+    #[test]
+    fn process_player_movement_right_click_outside_board_destination_is_clamped() {
+        let mut state = setup::setup_game_state_with_one_object();
+        state.basic_state.external_event = UserInput::RightMouse(Coordinates::new(700.0, 100.0));
+
+        let state = process_player_movement(state);
+        let destination = state.basic_state.movements[&0].get_destination().unwrap();
+
+        assert_eq!(*destination, Coordinates::new(462.0, 100.0));
+    }
+
+    #[test]
+    fn process_player_movement_selected_object_removed_right_click_is_ignored() {
+        let mut state = setup::setup_game_state_with_one_object();
+        state.basic_state.gameboard.remove_object(0).unwrap();
+        state.basic_state.external_event = UserInput::RightMouse(Coordinates::new(50.0, 50.0));
+
+        let state = process_player_movement(state);
+
+        assert_eq!(state.basic_state.movements.is_empty(), true);
+    }
+
+    #[test]
+    fn proces_movement_moving_object_removed_movement_is_dropped() {
+        let mut state = setup::setup_game_state_with_one_object();
+        state.basic_state.movements.insert(0, MovementHandler::start(Coordinates::new(0.0, 0.0), Coordinates::new(100.0, 0.0)));
+        state.basic_state.gameboard.remove_object(0).unwrap();
+        state.basic_state.external_event = UserInput::Update(0.5);
+
+        let state = proces_movement(state);
+
+        assert_eq!(state.basic_state.movements.is_empty(), true);
+    }
+
+    #[test]
+    fn proces_movement_update_event_object_moves_by_speed_times_delta_time() {
+        let mut state = setup::setup_game_state_with_one_object();
+        state.basic_state.movements.insert(0, MovementHandler::start(Coordinates::new(0.0, 0.0), Coordinates::new(100.0, 0.0)));
+        state.basic_state.gameboard.get_object_by_id_mut(0).unwrap().components.insert(Speed{value: 40.0});
+        state.basic_state.external_event = UserInput::Update(0.5);
+
+        let mut state = proces_movement(state);
+        let position = &state.get_basic_state().gameboard.get_object_by_id(0).unwrap().position;
+
+        assert_eq!(*position, Coordinates::new(20.0, 0.0));
+    }
+
+    #[test]
+    fn proces_movement_destination_reached_movement_finished_event_emitted() {
+        let mut state = setup::setup_game_state_with_one_object();
+        state.basic_state.movements.insert(0, MovementHandler::start(Coordinates::new(0.0, 0.0), Coordinates::new(10.0, 0.0)));
+        state.basic_state.external_event = UserInput::Update(1.0);
+
+        let state = proces_movement(state);
+
+        assert_eq!(state.basic_state.movements.len(), 0);
+        assert_eq!(state.basic_state.game_events, vec![GameEvent::MovementFinished(0)]);
+        assert_eq!(state.basic_state.gameboard.get_object_by_id(0).unwrap().position, Coordinates::new(10.0, 0.0));
+    }
+
+    #[test]
+    fn proces_movement_boxed_in_unit_gives_up() {
+        let mut state = setup::setup_game_state_with_one_object();
+        state.basic_state.gameboard.add_object(GameObject::new(GameObjectType::Static, Coordinates::new(50.0, 0.0), Size::new(20.0, 50.0))).unwrap();
+        state.basic_state.gameboard.add_object(GameObject::new(GameObjectType::Static, Coordinates::new(0.0, 50.0), Size::new(70.0, 20.0))).unwrap();
+        state.basic_state.movements.insert(0, MovementHandler::start(Coordinates::new(0.0, 0.0), Coordinates::new(200.0, 0.0)));
+
+        for _ in 0..MAX_STALLED_UPDATES - 1 {
+            state.basic_state.external_event = UserInput::Update(0.05);
+            state = proces_movement(state);
+        }
+        assert_eq!(state.basic_state.movements.contains_key(&0), true);
+        state = proces_movement(state);
+
+        assert_eq!(state.basic_state.movements.contains_key(&0), false);
+        assert_eq!(state.basic_state.game_events, vec![GameEvent::MovementFinished(0)]);
+    }
+
+    #[test]
+    fn proces_movement_two_units_heading_for_same_spot_both_give_up() {
+        let mut state = setup::setup_game_state_with_one_object();
+        state.basic_state.gameboard.add_object(setup::setup_selectable_object_at(Coordinates::new(200.0, 0.0))).unwrap();
+        state.basic_state.movements.insert(0, MovementHandler::start(Coordinates::new(0.0, 0.0), Coordinates::new(100.0, 0.0)));
+        state.basic_state.movements.insert(1, MovementHandler::start(Coordinates::new(200.0, 0.0), Coordinates::new(100.0, 0.0)));
+
+        for _ in 0..400 {
+            state.basic_state.external_event = UserInput::Update(0.05);
+            state = proces_movement(state);
+        }
+
+        assert_eq!(state.basic_state.movements.is_empty(), true);
+    }
+
+    #[test]
+    fn proces_movement_two_objects_moving_toward_each_other_never_overlap() {
+        let mut state = setup::setup_game_state_with_one_object();
+        state.basic_state.gameboard.add_object(setup::setup_selectable_object_at(Coordinates::new(200.0, 0.0))).unwrap();
+        state.basic_state.movements.insert(0, MovementHandler::start(Coordinates::new(0.0, 0.0), Coordinates::new(200.0, 0.0)));
+        state.basic_state.movements.insert(1, MovementHandler::start(Coordinates::new(200.0, 0.0), Coordinates::new(0.0, 0.0)));
+
+        for _ in 0..400 {
+            state.basic_state.external_event = UserInput::Update(0.05);
+            state = proces_movement(state);
+
+            let a = state.basic_state.gameboard.get_object_by_id(0).unwrap();
+            let b = state.basic_state.gameboard.get_object_by_id(1).unwrap();
+            assert_eq!(algebra_basics::check_if_rectangles_overlap(&a.position, &a.size, &b.position, &b.size), false);
+        }
+    }
+
+    #[test]
+    fn clear_game_events_events_from_previous_iteration_are_removed() {
+        let mut state = setup::setup_game_state_with_one_object();
+        state.basic_state.game_events.push(GameEvent::MovementFinished(0));
+
+        let state = clear_game_events(state);
+
+        assert_eq!(state.basic_state.game_events.is_empty(), true);
+    }
+
+    #[test]
+    fn proces_movement_no_update_event_object_doesent_move() {
+        let mut state = setup::setup_game_state_with_one_object();
+        state.basic_state.movements.insert(0, MovementHandler::start(Coordinates::new(0.0, 0.0), Coordinates::new(100.0, 0.0)));
+        state.basic_state.external_event = UserInput::NoInputCursorPos(Coordinates::new(0.0, 0.0));
+
+        let mut state = proces_movement(state);
+        let position = &state.get_basic_state().gameboard.get_object_by_id(0).unwrap().position;
+
+        assert_eq!(*position, Coordinates::new(0.0, 0.0));
+    }
+
     #[test]
     fn process_selection_left_click_on_selectable_selected_id_changes() {
         let mut state = setup::setup_game_state_with_one_object();
-        let new_object = GameObject::new(GameObjectType::Selectable, Coordinates::new(100.0, 100.0), Size::new(50.0, 50.0));
+        let new_object = setup::setup_selectable_object_at(Coordinates::new(100.0, 100.0));
         
-        state.basic_state.gameboard.add_object(new_object);
+        state.basic_state.gameboard.add_object(new_object).unwrap();
         state.basic_state.external_event = UserInput::LeftMouse(Coordinates::new(125.0, 125.0));
 
         state = process_selection(state);
-        let selected_id = state.basic_state.current_selected_id;
+        let selected_ids: Vec<u32> = state.basic_state.selected_ids.iter().cloned().collect();
 
-        assert_eq!(selected_id, 1);
+        assert_eq!(selected_ids, vec![1]);
     }
 
     #[test]
@@ -144,8 +577,242 @@ mod tests {
         state.basic_state.external_event = UserInput::LeftMouse(Coordinates::new(125.0, 125.0));
 
         state = process_selection(state);
-        let selected_id = state.basic_state.current_selected_id;
+        let selected_ids: Vec<u32> = state.basic_state.selected_ids.iter().cloned().collect();
 
-        assert_eq!(selected_id, 0);
+        assert_eq!(selected_ids, vec![0]);
     }
-}
\ No newline at end of file
+
+    #[test]
+    fn process_player_movement_right_click_with_many_selected_objects_each_gets_own_destination() {
+        let mut state = setup::setup_game_state_with_one_object();
+        state.basic_state.gameboard.add_object(setup::setup_selectable_object_at(Coordinates::new(100.0, 0.0))).unwrap();
+        state.basic_state.selected_ids.insert(1);
+        state.basic_state.external_event = UserInput::RightMouse(Coordinates::new(50.0, 300.0));
+
+        let state = process_player_movement(state);
+        let destination_0 = state.basic_state.movements[&0].get_destination().unwrap();
+        let destination_1 = state.basic_state.movements[&1].get_destination().unwrap();
+
+        assert_ne!(destination_0, destination_1);
+    }
+
+    #[test]
+    fn process_formation_selection_select_formation_input_formation_changes() {
+        let mut state = setup::setup_game_state_with_one_object();
+        state.basic_state.external_event = UserInput::SelectFormation(Formation::Wedge);
+
+        let state = process_formation_selection(state);
+
+        assert_eq!(state.basic_state.formation, Formation::Wedge);
+    }
+
+    #[test]
+    fn process_selection_shift_left_click_on_unselected_object_object_added_to_selection() {
+        let mut state = setup::setup_game_state_with_one_object();
+        let new_object = setup::setup_selectable_object_at(Coordinates::new(100.0, 100.0));
+
+        state.basic_state.gameboard.add_object(new_object).unwrap();
+        state.basic_state.external_event = UserInput::ShiftLeftMouse(Coordinates::new(125.0, 125.0));
+
+        state = process_selection(state);
+        let selected_ids: Vec<u32> = state.basic_state.selected_ids.iter().cloned().collect();
+
+        assert_eq!(selected_ids, vec![0, 1]);
+    }
+
+    #[test]
+    fn process_selection_shift_left_click_on_selected_object_object_removed_from_selection() {
+        let mut state = setup::setup_game_state_with_one_object();
+        state.basic_state.external_event = UserInput::ShiftLeftMouse(Coordinates::new(25.0, 25.0));
+
+        state = process_selection(state);
+
+        assert_eq!(state.basic_state.selected_ids.is_empty(), true);
+    }
+
+    #[test]
+    fn process_selection_selection_box_objects_inside_box_replace_selection() {
+        let mut state = setup::setup_game_state_with_one_object();
+        state.basic_state.gameboard.add_object(setup::setup_selectable_object_at(Coordinates::new(100.0, 100.0))).unwrap();
+        state.basic_state.gameboard.add_object(setup::setup_selectable_object_at(Coordinates::new(200.0, 100.0))).unwrap();
+        state.basic_state.external_event = UserInput::SelectionBox{start: Coordinates::new(260.0, 90.0), end: Coordinates::new(90.0, 160.0), add: false};
+
+        state = process_selection(state);
+        let selected_ids: Vec<u32> = state.basic_state.selected_ids.iter().cloned().collect();
+
+        assert_eq!(selected_ids, vec![1, 2]);
+    }
+
+    #[test]
+    fn process_selection_left_click_on_static_object_selection_doesent_change() {
+        let mut state = setup::setup_game_state_with_one_object();
+        state.basic_state.gameboard.add_object(GameObject::new(GameObjectType::Static, Coordinates::new(100.0, 100.0), Size::new(50.0, 50.0))).unwrap();
+        state.basic_state.external_event = UserInput::LeftMouse(Coordinates::new(125.0, 125.0));
+
+        state = process_selection(state);
+        let selected_ids: Vec<u32> = state.basic_state.selected_ids.iter().cloned().collect();
+
+        assert_eq!(selected_ids, vec![0]);
+    }
+
+    #[test]
+    fn process_selection_selection_box_only_selectable_objects_are_selected() {
+        let mut state = setup::setup_game_state_with_one_object();
+        state.basic_state.gameboard.add_object(GameObject::new(GameObjectType::Static, Coordinates::new(100.0, 0.0), Size::new(50.0, 50.0))).unwrap();
+        state.basic_state.gameboard.add_object(GameObject::new(GameObjectType::Interactable, Coordinates::new(200.0, 0.0), Size::new(50.0, 50.0))).unwrap();
+        state.basic_state.external_event = UserInput::SelectionBox{start: Coordinates::new(0.0, 0.0), end: Coordinates::new(300.0, 60.0), add: false};
+
+        state = process_selection(state);
+        let selected_ids: Vec<u32> = state.basic_state.selected_ids.iter().cloned().collect();
+
+        assert_eq!(selected_ids, vec![0]);
+    }
+
+    #[test]
+    fn process_selection_left_click_on_enemy_unit_selection_doesent_change() {
+        let mut state = setup::setup_game_state_with_one_object();
+        let mut enemy = setup::setup_selectable_object_at(Coordinates::new(100.0, 100.0));
+        enemy.components.insert(Team{id: 1});
+        state.basic_state.gameboard.add_object(enemy).unwrap();
+        state.basic_state.external_event = UserInput::LeftMouse(Coordinates::new(125.0, 125.0));
+
+        state = process_selection(state);
+        let selected_ids: Vec<u32> = state.basic_state.selected_ids.iter().cloned().collect();
+
+        assert_eq!(selected_ids, vec![0]);
+    }
+
+    #[test]
+    fn process_player_movement_enemy_unit_selected_it_gets_no_movement() {
+        let mut state = setup::setup_game_state_with_one_object();
+        let mut enemy = setup::setup_selectable_object_at(Coordinates::new(100.0, 100.0));
+        enemy.components.insert(Team{id: 1});
+        state.basic_state.gameboard.add_object(enemy).unwrap();
+        state.basic_state.selected_ids.insert(1);
+        state.basic_state.external_event = UserInput::RightMouse(Coordinates::new(300.0, 300.0));
+
+        let state = process_player_movement(state);
+
+        assert_eq!(state.basic_state.movements.contains_key(&1), false);
+    }
+
+    #[test]
+    fn process_player_movement_static_object_selected_it_gets_no_movement() {
+        let mut state = setup::setup_game_state_with_one_object();
+        state.basic_state.gameboard.add_object(GameObject::new(GameObjectType::Static, Coordinates::new(100.0, 100.0), Size::new(50.0, 50.0))).unwrap();
+        state.basic_state.selected_ids.insert(1);
+        state.basic_state.external_event = UserInput::RightMouse(Coordinates::new(300.0, 300.0));
+
+        let state = process_player_movement(state);
+
+        assert_eq!(state.basic_state.movements.contains_key(&1), false);
+        assert_eq!(state.basic_state.movements.contains_key(&0), true);
+    }
+
+    #[test]
+    fn process_player_movement_right_click_on_interactable_unit_walks_up_to_it() {
+        let mut state = setup::setup_game_state_with_one_object();
+        let door = GameObject::new(GameObjectType::Interactable, Coordinates::new(200.0, 0.0), Size::new(20.0, 60.0));
+        let door_id = state.basic_state.gameboard.add_object(door).unwrap();
+        state.basic_state.external_event = UserInput::RightMouse(Coordinates::new(210.0, 30.0));
+
+        let state = process_player_movement(state);
+
+        assert_eq!(state.basic_state.movements[&0].get_destination(), Some(&Coordinates::new(149.0, 0.0)));
+        assert_eq!(state.basic_state.interaction_orders.get(&0), Some(&door_id));
+    }
+
+    #[test]
+    fn process_player_movement_move_order_after_interact_order_interaction_is_cancelled() {
+        let mut state = setup::setup_game_state_with_one_object();
+        state.basic_state.interaction_orders.insert(0, 5);
+        state.basic_state.external_event = UserInput::RightMouse(Coordinates::new(300.0, 300.0));
+
+        let state = process_player_movement(state);
+
+        assert_eq!(state.basic_state.interaction_orders.is_empty(), true);
+    }
+
+    #[test]
+    fn process_player_movement_right_click_every_selected_object_gets_movement() {
+        let mut state = setup::setup_game_state_with_one_object();
+        state.basic_state.gameboard.add_object(setup::setup_selectable_object_at(Coordinates::new(100.0, 100.0))).unwrap();
+        state.basic_state.selected_ids.insert(1);
+        state.basic_state.external_event = UserInput::RightMouse(Coordinates::new(300.0, 300.0));
+
+        let state = process_player_movement(state);
+
+        assert_eq!(state.basic_state.movements.len(), 2);
+    }
+
+    #[test]
+    fn process_player_movement_right_click_on_enemy_armed_unit_gets_attack_order() {
+        let mut state = setup::setup_game_state_with_one_object();
+        state.basic_state.gameboard.get_object_by_id_mut(0).unwrap().components.insert(Weapon{damage: 10.0, range: 20.0});
+        let mut enemy = setup::setup_selectable_object_at(Coordinates::new(200.0, 200.0));
+        enemy.components.insert(Team{id: setup::LOCAL_TEAM + 1});
+        enemy.components.insert(Health{current: 100.0, max: 100.0});
+        let enemy_id = state.basic_state.gameboard.add_object(enemy).unwrap();
+        state.basic_state.external_event = UserInput::RightMouse(Coordinates::new(210.0, 210.0));
+
+        let state = process_player_movement(state);
+
+        assert_eq!(state.basic_state.attack_orders.get(&0), Some(&enemy_id));
+        assert_eq!(state.basic_state.movements.is_empty(), true);
+    }
+
+    #[test]
+    fn process_player_movement_right_click_on_enemy_in_fog_no_attack_order() {
+        let mut state = setup::setup_game_state_with_one_object();
+        state.basic_state.gameboard.get_object_by_id_mut(0).unwrap().components.insert(Weapon{damage: 10.0, range: 20.0});
+        let mut enemy = setup::setup_selectable_object_at(Coordinates::new(200.0, 200.0));
+        enemy.components.insert(Team{id: setup::LOCAL_TEAM + 1});
+        enemy.components.insert(Health{current: 100.0, max: 100.0});
+        state.basic_state.gameboard.add_object(enemy).unwrap();
+        let map = VisibilityMap::new(state.basic_state.gameboard.get_bounds());
+        state.basic_state.gameboard.set_visibility(setup::LOCAL_TEAM, map);
+        state.basic_state.external_event = UserInput::RightMouse(Coordinates::new(210.0, 210.0));
+
+        let state = process_player_movement(state);
+
+        assert_eq!(state.basic_state.attack_orders.is_empty(), true);
+    }
+
+    #[test]
+    fn process_player_movement_turn_based_enemy_in_range_struck_right_away() {
+        let mut state = setup::setup_game_state_with_one_object();
+        let unit = state.basic_state.gameboard.get_object_by_id_mut(0).unwrap();
+        unit.components.insert(Weapon{damage: 10.0, range: 20.0});
+        unit.components.insert(ActionPoints{current: 0.0, max: 10.0});
+        let mut enemy = setup::setup_selectable_object_at(Coordinates::new(60.0, 0.0));
+        enemy.components.insert(Team{id: setup::LOCAL_TEAM + 1});
+        enemy.components.insert(Health{current: 100.0, max: 100.0});
+        let enemy_id = state.basic_state.gameboard.add_object(enemy).unwrap();
+        state.basic_state.game_mode = GameMode::turn_based(&mut state.basic_state.gameboard, setup::LOCAL_TEAM);
+        state.basic_state.external_event = UserInput::RightMouse(Coordinates::new(70.0, 10.0));
+
+        let state = process_player_movement(state);
+
+        let gameboard = &state.basic_state.gameboard;
+        assert_eq!(gameboard.get_component::<Health>(enemy_id).unwrap().current, 90.0);
+        assert_eq!(gameboard.get_component::<ActionPoints>(0).unwrap().current, 10.0 - turns::ATTACK_COST);
+        assert_eq!(state.basic_state.attack_orders.is_empty(), true);
+    }
+
+    #[test]
+    fn process_player_movement_turn_of_other_team_no_movement() {
+        let mut state = setup::setup_game_state_with_one_object();
+        state.basic_state.game_mode = GameMode::TurnBased(turns::TurnState {
+            teams: vec![setup::LOCAL_TEAM, setup::LOCAL_TEAM + 1],
+            active: 1,
+            number: 1,
+            previews: Vec::new(),
+            preview_cursor: None
+        });
+        state.basic_state.external_event = UserInput::RightMouse(Coordinates::new(300.0, 300.0));
+
+        let state = process_player_movement(state);
+
+        assert_eq!(state.basic_state.movements.is_empty(), true);
+    }
+}
@@ -0,0 +1,397 @@
+use std::cmp::Ordering;
+use std::collections::BinaryHeap;
+
+use crate::algebra_basics::{Coordinates, Size};
+use crate::game_data::gameboard::Gameboard;
+use crate::game_data::game_object::GameObject;
+
+pub const DEFAULT_CELL_SIZE: f64 = 10.0;
+
+const NEIGHBOUR_OFFSETS: [(i64, i64); 8] = [
+    (1, 0), (-1, 0), (0, 1), (0, -1),
+    (1, 1), (1, -1), (-1, 1), (-1, -1)
+];
+
+// Grid of cells covering the gameboard, where a cell is blocked when a unit of the given size
+// placed with its position anywhere in that cell would overlap an obstacle, or when no position
+// in that cell keeps the unit on the board.
+pub struct NavigationGrid {
+    origin: Coordinates,
+    // Furthest position at which the unit still lies on the board.
+    position_limit: Coordinates,
+    cell_size: f64,
+    columns: usize,
+    rows: usize,
+    blocked: Vec<bool>
+}
+
+impl NavigationGrid {
+    pub fn build(gameboard: &Gameboard, unit_size: &Size, cell_size: f64, area: (&Coordinates, &Coordinates)) -> NavigationGrid {
+        let obstacles: Vec<&GameObject> = gameboard.get_all_objects()
+            .into_iter()
+            .filter(|object| object.is_obstacle())
+            .collect();
+
+        let (min, max) = get_grid_extents(&obstacles, unit_size, cell_size, area);
+        let columns = ((max.x - min.x) / cell_size).ceil() as usize;
+        let rows = ((max.y - min.y) / cell_size).ceil() as usize;
+
+        let bounds = gameboard.get_bounds();
+        let mut grid = NavigationGrid {
+            origin: min,
+            position_limit: Coordinates::new(bounds.width - unit_size.width, bounds.height - unit_size.height),
+            cell_size,
+            columns,
+            rows,
+            blocked: vec![false; columns * rows]
+        };
+
+        for obstacle in obstacles {
+            grid.block_inflated_obstacle(obstacle, unit_size);
+        }
+        grid.block_outside_bounds();
+
+        grid
+    }
+
+    pub fn find_path(&self, start: &Coordinates, destination: &Coordinates) -> Vec<Coordinates> {
+        let start_cell = self.get_cell(start);
+        let destination_cell = self.get_cell(destination);
+
+        if self.is_blocked(destination_cell) {
+            return Vec::new();
+        }
+
+        let cells = match self.search(start_cell, destination_cell) {
+            Some(cells) => cells,
+            None => return Vec::new()
+        };
+
+        let mut path: Vec<Coordinates> = remove_collinear_cells(&cells)
+            .into_iter()
+            .skip(1)
+            .map(|cell| self.get_cell_center(cell))
+            .collect();
+
+        path.pop();
+        path.push(destination.clone());
+        path
+    }
+
+    fn search(&self, start: (usize, usize), destination: (usize, usize)) -> Option<Vec<(usize, usize)>> {
+        let mut costs = vec![f64::MAX; self.columns * self.rows];
+        let mut came_from: Vec<Option<usize>> = vec![None; self.columns * self.rows];
+        let mut open = BinaryHeap::new();
+
+        let start_index = self.get_index(start);
+        let destination_index = self.get_index(destination);
+        costs[start_index] = 0.0;
+        open.push(OpenNode { estimate: self.octile_distance(start, destination), index: start_index });
+
+        while let Some(OpenNode { index, .. }) = open.pop() {
+            if index == destination_index {
+                return Some(self.reconstruct(&came_from, index));
+            }
+
+            let cell = self.get_cell_from_index(index);
+            for (offset_x, offset_y) in NEIGHBOUR_OFFSETS.iter() {
+                let neighbour = match self.get_free_neighbour(cell, *offset_x, *offset_y) {
+                    Some(neighbour) => neighbour,
+                    None => continue
+                };
+
+                let step = if *offset_x != 0 && *offset_y != 0 { std::f64::consts::SQRT_2 } else { 1.0 };
+                let cost = costs[index] + step * self.cell_size;
+                let neighbour_index = self.get_index(neighbour);
+
+                if cost < costs[neighbour_index] {
+                    costs[neighbour_index] = cost;
+                    came_from[neighbour_index] = Some(index);
+                    open.push(OpenNode { estimate: cost + self.octile_distance(neighbour, destination), index: neighbour_index });
+                }
+            }
+        }
+
+        return None;
+    }
+
+    // Diagonal steps aren't allowed to cut the corner of a blocked cell.
+    fn get_free_neighbour(&self, cell: (usize, usize), offset_x: i64, offset_y: i64) -> Option<(usize, usize)> {
+        let neighbour = self.offset_cell(cell, offset_x, offset_y)?;
+        if self.is_blocked(neighbour) {
+            return None;
+        }
+
+        if offset_x != 0 && offset_y != 0 {
+            let side_x = self.offset_cell(cell, offset_x, 0)?;
+            let side_y = self.offset_cell(cell, 0, offset_y)?;
+            if self.is_blocked(side_x) || self.is_blocked(side_y) {
+                return None;
+            }
+        }
+
+        Some(neighbour)
+    }
+
+    fn offset_cell(&self, cell: (usize, usize), offset_x: i64, offset_y: i64) -> Option<(usize, usize)> {
+        let x = cell.0 as i64 + offset_x;
+        let y = cell.1 as i64 + offset_y;
+
+        if x < 0 || y < 0 || x >= self.columns as i64 || y >= self.rows as i64 {
+            return None;
+        }
+
+        Some((x as usize, y as usize))
+    }
+
+    fn reconstruct(&self, came_from: &[Option<usize>], mut index: usize) -> Vec<(usize, usize)> {
+        let mut cells = vec![self.get_cell_from_index(index)];
+
+        while let Some(previous) = came_from[index] {
+            cells.push(self.get_cell_from_index(previous));
+            index = previous;
+        }
+
+        cells.reverse();
+        cells
+    }
+
+    fn octile_distance(&self, a: (usize, usize), b: (usize, usize)) -> f64 {
+        let dx = (a.0 as f64 - b.0 as f64).abs();
+        let dy = (a.1 as f64 - b.1 as f64).abs();
+
+        (dx + dy + (std::f64::consts::SQRT_2 - 2.0) * dx.min(dy)) * self.cell_size
+    }
+
+    fn block_inflated_obstacle(&mut self, obstacle: &GameObject, unit_size: &Size) {
+        let min_x = obstacle.position.x - unit_size.width;
+        let min_y = obstacle.position.y - unit_size.height;
+        let max_x = obstacle.position.x + obstacle.size.width;
+        let max_y = obstacle.position.y + obstacle.size.height;
+
+        for y in 0..self.rows {
+            for x in 0..self.columns {
+                let cell_x = self.origin.x + x as f64 * self.cell_size;
+                let cell_y = self.origin.y + y as f64 * self.cell_size;
+
+                if cell_x < max_x && cell_x + self.cell_size > min_x
+                    && cell_y < max_y && cell_y + self.cell_size > min_y {
+                    self.blocked[y * self.columns + x] = true;
+                }
+            }
+        }
+    }
+
+    fn block_outside_bounds(&mut self) {
+        for y in 0..self.rows {
+            for x in 0..self.columns {
+                let cell_x = self.origin.x + x as f64 * self.cell_size;
+                let cell_y = self.origin.y + y as f64 * self.cell_size;
+
+                if cell_x + self.cell_size <= 0.0 || cell_x > self.position_limit.x
+                    || cell_y + self.cell_size <= 0.0 || cell_y > self.position_limit.y {
+                    self.blocked[y * self.columns + x] = true;
+                }
+            }
+        }
+    }
+
+    fn is_blocked(&self, cell: (usize, usize)) -> bool {
+        self.blocked[self.get_index(cell)]
+    }
+
+    fn get_cell(&self, coordinates: &Coordinates) -> (usize, usize) {
+        let x = ((coordinates.x - self.origin.x) / self.cell_size).floor().max(0.0) as usize;
+        let y = ((coordinates.y - self.origin.y) / self.cell_size).floor().max(0.0) as usize;
+
+        (x.min(self.columns - 1), y.min(self.rows - 1))
+    }
+
+    // Cells on the board edge are only partly on the board, their center is moved onto it.
+    fn get_cell_center(&self, cell: (usize, usize)) -> Coordinates {
+        let x = self.origin.x + (cell.0 as f64 + 0.5) * self.cell_size;
+        let y = self.origin.y + (cell.1 as f64 + 0.5) * self.cell_size;
+        Coordinates::new(x.max(0.0).min(self.position_limit.x), y.max(0.0).min(self.position_limit.y))
+    }
+
+    fn get_index(&self, cell: (usize, usize)) -> usize {
+        cell.1 * self.columns + cell.0
+    }
+
+    fn get_cell_from_index(&self, index: usize) -> (usize, usize) {
+        (index % self.columns, index / self.columns)
+    }
+}
+
+// Destinations off the board are moved onto its edge.
+pub fn find_path(start: &Coordinates, destination: &Coordinates, unit_size: &Size, gameboard: &Gameboard) -> Vec<Coordinates> {
+    let destination = gameboard.clamp_position(destination, unit_size);
+    let grid = NavigationGrid::build(gameboard, unit_size, DEFAULT_CELL_SIZE, (start, &destination));
+    grid.find_path(start, &destination)
+}
+
+// The grid covers every obstacle and both ends of the path, with a margin of one unit and one cell.
+fn get_grid_extents(obstacles: &[&GameObject], unit_size: &Size, cell_size: f64, area: (&Coordinates, &Coordinates)) -> (Coordinates, Coordinates) {
+    let (start, destination) = area;
+    let mut min = Coordinates::new(start.x.min(destination.x), start.y.min(destination.y));
+    let mut max = Coordinates::new(start.x.max(destination.x), start.y.max(destination.y));
+
+    for obstacle in obstacles {
+        min.x = min.x.min(obstacle.position.x);
+        min.y = min.y.min(obstacle.position.y);
+        max.x = max.x.max(obstacle.position.x + obstacle.size.width);
+        max.y = max.y.max(obstacle.position.y + obstacle.size.height);
+    }
+
+    let margin_x = unit_size.width + cell_size;
+    let margin_y = unit_size.height + cell_size;
+
+    (Coordinates::new(min.x - margin_x, min.y - margin_y), Coordinates::new(max.x + margin_x, max.y + margin_y))
+}
+
+fn remove_collinear_cells(cells: &[(usize, usize)]) -> Vec<(usize, usize)> {
+    let mut result = Vec::new();
+
+    for (i, cell) in cells.iter().enumerate() {
+        if i == 0 || i == cells.len() - 1 {
+            result.push(*cell);
+            continue;
+        }
+
+        let previous = cells[i - 1];
+        let next = cells[i + 1];
+        let incoming = (cell.0 as i64 - previous.0 as i64, cell.1 as i64 - previous.1 as i64);
+        let outgoing = (next.0 as i64 - cell.0 as i64, next.1 as i64 - cell.1 as i64);
+
+        if incoming != outgoing {
+            result.push(*cell);
+        }
+    }
+
+    result
+}
+
+#[derive(PartialEq)]
+struct OpenNode {
+    estimate: f64,
+    index: usize
+}
+
+impl Eq for OpenNode {}
+
+// Reversed, so that BinaryHeap pops the node with the lowest estimate first.
+impl Ord for OpenNode {
+    fn cmp(&self, other: &Self) -> Ordering {
+        other.estimate.partial_cmp(&self.estimate)
+            .unwrap_or(Ordering::Equal)
+            .then_with(|| other.index.cmp(&self.index))
+    }
+}
+
+impl PartialOrd for OpenNode {
+    fn partial_cmp(&self, other: &Self) -> Option<Ordering> {
+        Some(self.cmp(other))
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use crate::game_data::game_object::GameObjectType;
+    use crate::game_data::components::Interaction;
+
+    fn setup_gameboard_with_wall(object_type: GameObjectType) -> Gameboard {
+        let mut gameboard = Gameboard::new();
+        let wall = GameObject::new(object_type, Coordinates::new(100.0, 0.0), Size::new(20.0, 150.0));
+        gameboard.add_object(wall).unwrap();
+
+        return gameboard;
+    }
+
+    fn check_if_position_overlaps_wall(position: &Coordinates, unit_size: &Size) -> bool {
+        position.x < 120.0 && position.x + unit_size.width > 100.0
+            && position.y < 150.0 && position.y + unit_size.height > 0.0
+    }
+
+    #[test]
+    fn find_path_no_obstacles_path_with_1_point_is_calculated() {
+        let gameboard = Gameboard::new();
+        let destination = Coordinates::new(200.0, 0.0);
+
+        let path = find_path(&Coordinates::new(0.0, 0.0), &destination, &Size::new(10.0, 10.0), &gameboard);
+
+        assert_eq!(path, vec![destination]);
+    }
+
+    #[test]
+    fn find_path_static_wall_between_start_and_destination_path_goes_around_wall() {
+        let gameboard = setup_gameboard_with_wall(GameObjectType::Static);
+        let unit_size = Size::new(10.0, 10.0);
+        let destination = Coordinates::new(200.0, 20.0);
+
+        let path = find_path(&Coordinates::new(20.0, 20.0), &destination, &unit_size, &gameboard);
+
+        assert_eq!(path.len() > 1, true);
+        assert_eq!(path.last(), Some(&destination));
+        for point in &path {
+            assert_eq!(check_if_position_overlaps_wall(point, &unit_size), false);
+        }
+    }
+
+    #[test]
+    fn find_path_selectable_object_between_start_and_destination_path_with_1_point_is_calculated() {
+        let gameboard = setup_gameboard_with_wall(GameObjectType::Selectable);
+        let destination = Coordinates::new(200.0, 20.0);
+
+        let path = find_path(&Coordinates::new(20.0, 20.0), &destination, &Size::new(10.0, 10.0), &gameboard);
+
+        assert_eq!(path, vec![destination]);
+    }
+
+    #[test]
+    fn find_path_open_door_between_start_and_destination_path_goes_through_it() {
+        let mut gameboard = Gameboard::new();
+        let door = GameObject::new(GameObjectType::Interactable, Coordinates::new(100.0, 0.0), Size::new(20.0, 150.0))
+            .with_interaction(Interaction::Door{open: true});
+        gameboard.add_object(door).unwrap();
+        let destination = Coordinates::new(200.0, 20.0);
+
+        let path = find_path(&Coordinates::new(20.0, 20.0), &destination, &Size::new(10.0, 10.0), &gameboard);
+
+        assert_eq!(path, vec![destination]);
+    }
+
+    #[test]
+    fn find_path_destination_inside_inflated_obstacle_returns_empty_path() {
+        let gameboard = setup_gameboard_with_wall(GameObjectType::Interactable);
+
+        let path = find_path(&Coordinates::new(20.0, 20.0), &Coordinates::new(95.0, 20.0), &Size::new(10.0, 10.0), &gameboard);
+
+        assert_eq!(path.is_empty(), true);
+    }
+
+    #[test]
+    fn find_path_destination_outside_board_path_ends_on_board_edge() {
+        let gameboard = setup_gameboard_with_wall(GameObjectType::Static);
+        let unit_size = Size::new(10.0, 10.0);
+
+        let path = find_path(&Coordinates::new(20.0, 20.0), &Coordinates::new(600.0, -40.0), &unit_size, &gameboard);
+
+        assert_eq!(path.last(), Some(&Coordinates::new(502.0, 0.0)));
+        for point in &path {
+            assert_eq!(gameboard.check_if_area_is_within_bounds(point, &unit_size), true);
+        }
+    }
+
+    #[test]
+    fn navigation_grid_octile_distance_diagonal_and_straight_steps_are_summed() {
+        let gameboard = Gameboard::new();
+        let area = (&Coordinates::new(0.0, 0.0), &Coordinates::new(100.0, 100.0));
+        let grid = NavigationGrid::build(&gameboard, &Size::new(0.0, 0.0), 1.0, area);
+
+        let distance = grid.octile_distance((0, 0), (3, 1));
+        let expected = 2.0 + std::f64::consts::SQRT_2;
+
+        assert_eq!((distance - expected).abs() < 0.000_001, true);
+    }
+}
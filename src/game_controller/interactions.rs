@@ -0,0 +1,252 @@
+use crate::algebra_basics::{Coordinates, Size};
+use crate::algebra_basics;
+use crate::game_data::gameboard::{Gameboard, GameboardObjectOperation, GameboardError};
+use crate::game_data::game_object::GameObject;
+use crate::game_data::components::{Interaction, Inventory};
+use crate::game_controller::game_managers::{BasicState, BasicStateContainer, GameEvent};
+
+// Free space left between a unit and the object it walks up to.
+const ADJACENT_GAP: f64 = 1.0;
+// Largest gap between a unit and an object at which the unit can still interact with it.
+pub const INTERACTION_RANGE: f64 = 5.0;
+
+// Position closest to the given one at which a unit of the given size stands right next to the target.
+pub fn get_adjacent_position(position: &Coordinates, size: &Size, target: &GameObject) -> Coordinates {
+    let min_x = target.position.x - size.width - ADJACENT_GAP;
+    let min_y = target.position.y - size.height - ADJACENT_GAP;
+    let max_x = target.position.x + target.size.width + ADJACENT_GAP;
+    let max_y = target.position.y + target.size.height + ADJACENT_GAP;
+
+    let x = position.x.max(min_x).min(max_x);
+    let y = position.y.max(min_y).min(max_y);
+
+    if x == min_x || x == max_x || y == min_y || y == max_y {
+        return Coordinates::new(x, y);
+    }
+
+    // A unit standing on the target leaves through the closest edge.
+    let exits = [
+        (x - min_x, Coordinates::new(min_x, y)),
+        (max_x - x, Coordinates::new(max_x, y)),
+        (y - min_y, Coordinates::new(x, min_y)),
+        (max_y - y, Coordinates::new(x, max_y))
+    ];
+
+    return exits.iter()
+        .min_by(|a, b| a.0.partial_cmp(&b.0).unwrap())
+        .map(|(_, exit)| exit.clone())
+        .unwrap();
+}
+
+pub fn check_if_within_interaction_range(gameboard: &Gameboard, unit_id: u32, target_id: u32) -> bool {
+    let (unit, target) = match (gameboard.get_object_by_id(unit_id), gameboard.get_object_by_id(target_id)) {
+        (Some(unit), Some(target)) => (unit, target),
+        _ => return false
+    };
+
+    let reach_position = Coordinates::new(unit.position.x - INTERACTION_RANGE, unit.position.y - INTERACTION_RANGE);
+    let reach_size = Size::new(unit.size.width + 2.0 * INTERACTION_RANGE, unit.size.height + 2.0 * INTERACTION_RANGE);
+
+    return algebra_basics::check_if_rectangles_overlap(&reach_position, &reach_size, &target.position, &target.size);
+}
+
+// Carries out what the target does when the unit interacts with it. Picked up objects are removed
+// through the basic state, so that no order or selection keeps referring to them.
+pub fn apply_interaction(basic_state: &mut BasicState, unit_id: u32, target_id: u32) -> Result<(), GameboardError> {
+    let gameboard = &mut basic_state.gameboard;
+    let interaction = gameboard.get_object_by_id(target_id)
+        .ok_or(GameboardError::UnknownId(target_id))?
+        .components.get::<Interaction>().cloned();
+
+    match interaction {
+        Some(Interaction::Door{..}) => toggle(gameboard, target_id),
+        Some(Interaction::Pickup{item}) => {
+            if gameboard.get_object_by_id(unit_id).is_none() {
+                return Err(GameboardError::UnknownId(unit_id));
+            }
+
+            basic_state.remove_object(target_id)?;
+            if let Some(unit) = basic_state.gameboard.get_object_by_id_mut(unit_id) {
+                match unit.components.get_mut::<Inventory>() {
+                    Some(inventory) => inventory.items.push(item),
+                    None => unit.components.insert(Inventory{items: vec![item]})
+                }
+            }
+
+            return Ok(());
+        },
+        Some(Interaction::CapturePoint{..}) => {
+            let captured = Interaction::CapturePoint{captured_by: Some(unit_id)};
+            gameboard.execute_operation(target_id, GameboardObjectOperation::SetInteraction(captured))
+        },
+        Some(Interaction::Switch{targets}) => {
+            for id in targets {
+                toggle(gameboard, id)?;
+            }
+
+            return Ok(());
+        },
+        None => Ok(())
+    }
+}
+
+// Opens closed doors and closes open ones, other objects don't react to being toggled.
+fn toggle(gameboard: &mut Gameboard, id: u32) -> Result<(), GameboardError> {
+    let interaction = gameboard.get_object_by_id(id)
+        .ok_or(GameboardError::UnknownId(id))?
+        .components.get::<Interaction>().cloned();
+
+    if let Some(Interaction::Door{open}) = interaction {
+        gameboard.execute_operation(id, GameboardObjectOperation::SetInteraction(Interaction::Door{open: !open}))?;
+    }
+
+    return Ok(());
+}
+
+// Fires the interaction of every unit which finished walking up to its target this iteration.
+// Units which ended up somewhere else drop the order.
+pub fn process_interaction_orders<S>(mut state: S) -> S
+where S: BasicStateContainer {
+    let basic_state = state.get_basic_state();
+
+    let finished: Vec<u32> = basic_state.game_events.iter()
+        .filter_map(|event| match event {
+            GameEvent::MovementFinished(id) => Some(*id),
+            _ => None
+        })
+        .collect();
+
+    for unit_id in finished {
+        if let Some(target_id) = basic_state.interaction_orders.remove(&unit_id) {
+            if !check_if_within_interaction_range(&basic_state.gameboard, unit_id, target_id) {
+                continue;
+            }
+
+            basic_state.game_events.push(GameEvent::Interaction{unit_id, target_id});
+            if let Err(error) = apply_interaction(basic_state, unit_id, target_id) {
+                println!("Error: {}", error);
+            }
+        }
+    }
+
+    return state;
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use crate::testing::setup;
+    use crate::game_data::game_object::GameObjectType;
+    use crate::game_data::gameboard::GameboardObjectOperation;
+
+    fn setup_door() -> GameObject {
+        GameObject::new(GameObjectType::Interactable, Coordinates::new(200.0, 100.0), Size::new(20.0, 60.0))
+    }
+
+    #[test]
+    fn get_adjacent_position_unit_left_of_target_stands_at_left_edge() {
+        let result = get_adjacent_position(&Coordinates::new(0.0, 110.0), &Size::new(50.0, 50.0), &setup_door());
+
+        assert_eq!(result, Coordinates::new(149.0, 110.0));
+    }
+
+    #[test]
+    fn get_adjacent_position_unit_on_target_leaves_through_closest_edge() {
+        let result = get_adjacent_position(&Coordinates::new(215.0, 110.0), &Size::new(10.0, 10.0), &setup_door());
+
+        assert_eq!(result, Coordinates::new(221.0, 110.0));
+    }
+
+    #[test]
+    fn process_interaction_orders_unit_arrived_next_to_target_interaction_event_emitted() {
+        let mut state = setup::setup_game_state_with_one_object();
+        let door_id = state.basic_state.gameboard.add_object(setup_door()).unwrap();
+        state.basic_state.gameboard.execute_operation(0, GameboardObjectOperation::Move(Coordinates::new(149.0, 110.0))).unwrap();
+        state.basic_state.interaction_orders.insert(0, door_id);
+        state.basic_state.game_events.push(GameEvent::MovementFinished(0));
+
+        let state = process_interaction_orders(state);
+
+        assert_eq!(state.basic_state.game_events.last(), Some(&GameEvent::Interaction{unit_id: 0, target_id: door_id}));
+        assert_eq!(state.basic_state.interaction_orders.is_empty(), true);
+    }
+
+    #[test]
+    fn apply_interaction_closed_door_door_becomes_passable() {
+        let mut state = setup::setup_game_state_with_one_object();
+        let gameboard = &mut state.basic_state.gameboard;
+        let door_id = gameboard.add_object(setup_door().with_interaction(Interaction::Door{open: false})).unwrap();
+
+        apply_interaction(&mut state.basic_state, 0, door_id).unwrap();
+
+        let gameboard = &state.basic_state.gameboard;
+        assert_eq!(gameboard.get_object_by_id(door_id).unwrap().is_obstacle(), false);
+    }
+
+    #[test]
+    fn apply_interaction_pickup_item_moves_to_unit_inventory() {
+        let mut state = setup::setup_game_state_with_one_object();
+        let gameboard = &mut state.basic_state.gameboard;
+        let item_id = gameboard.add_object(setup_door().with_interaction(Interaction::Pickup{item: "key".to_string()})).unwrap();
+
+        apply_interaction(&mut state.basic_state, 0, item_id).unwrap();
+
+        let gameboard = &state.basic_state.gameboard;
+        assert_eq!(gameboard.get_object_by_id(item_id).is_none(), true);
+        let inventory = gameboard.get_object_by_id(0).unwrap().components.get::<Inventory>().unwrap();
+        assert_eq!(inventory.items, vec!["key".to_string()]);
+    }
+
+    #[test]
+    fn apply_interaction_pickup_other_orders_on_item_dropped() {
+        let mut state = setup::setup_game_state_with_one_object();
+        let other_id = state.basic_state.gameboard.add_object(setup::setup_selectable_object_at(Coordinates::new(0.0, 300.0))).unwrap();
+        let item_id = state.basic_state.gameboard.add_object(setup_door().with_interaction(Interaction::Pickup{item: "key".to_string()})).unwrap();
+        state.basic_state.interaction_orders.insert(other_id, item_id);
+        state.basic_state.selected_ids.insert(item_id);
+
+        apply_interaction(&mut state.basic_state, 0, item_id).unwrap();
+
+        assert_eq!(state.basic_state.interaction_orders.is_empty(), true);
+        assert_eq!(state.basic_state.selected_ids.contains(&item_id), false);
+    }
+
+    #[test]
+    fn apply_interaction_capture_point_captured_by_unit() {
+        let mut state = setup::setup_game_state_with_one_object();
+        let gameboard = &mut state.basic_state.gameboard;
+        let point_id = gameboard.add_object(setup_door().with_interaction(Interaction::CapturePoint{captured_by: None})).unwrap();
+
+        apply_interaction(&mut state.basic_state, 0, point_id).unwrap();
+
+        let gameboard = &state.basic_state.gameboard;
+        assert_eq!(gameboard.get_component::<Interaction>(point_id), Some(&Interaction::CapturePoint{captured_by: Some(0)}));
+    }
+
+    #[test]
+    fn apply_interaction_switch_linked_doors_are_toggled() {
+        let mut state = setup::setup_game_state_with_one_object();
+        let gameboard = &mut state.basic_state.gameboard;
+        let door = GameObject::new(GameObjectType::Interactable, Coordinates::new(300.0, 0.0), Size::new(20.0, 60.0));
+        let door_id = gameboard.add_object(door.with_interaction(Interaction::Door{open: false})).unwrap();
+        let switch_id = gameboard.add_object(setup_door().with_interaction(Interaction::Switch{targets: vec![door_id]})).unwrap();
+
+        apply_interaction(&mut state.basic_state, 0, switch_id).unwrap();
+
+        let gameboard = &state.basic_state.gameboard;
+        assert_eq!(gameboard.get_component::<Interaction>(door_id), Some(&Interaction::Door{open: true}));
+    }
+
+    #[test]
+    fn process_interaction_orders_unit_stopped_far_from_target_order_dropped_without_event() {
+        let mut state = setup::setup_game_state_with_one_object();
+        let door_id = state.basic_state.gameboard.add_object(setup_door()).unwrap();
+        state.basic_state.interaction_orders.insert(0, door_id);
+        state.basic_state.game_events.push(GameEvent::MovementFinished(0));
+
+        let state = process_interaction_orders(state);
+
+        assert_eq!(state.basic_state.game_events, vec![GameEvent::MovementFinished(0)]);
+        assert_eq!(state.basic_state.interaction_orders.is_empty(), true);
+    }
+}
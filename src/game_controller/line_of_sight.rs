@@ -0,0 +1,105 @@
+use crate::algebra_basics::Coordinates;
+use crate::algebra_basics;
+use crate::game_data::gameboard::Gameboard;
+
+#[derive(Debug, PartialEq, Clone)]
+pub enum LineOfSight {
+    Clear,
+    Blocked{id: u32, point: Coordinates} // first opaque object on the way and where the segment enters it
+}
+
+impl LineOfSight {
+    pub fn is_clear(&self) -> bool {
+        *self == LineOfSight::Clear
+    }
+}
+
+pub fn line_of_sight(from: &Coordinates, to: &Coordinates, gameboard: &Gameboard) -> LineOfSight {
+    return get_line_of_sight_ignoring(from, to, gameboard, &[]);
+}
+
+// Line of sight between the middles of two objects. Neither of them blocks the view itself.
+pub fn check_if_objects_see_each_other(gameboard: &Gameboard, a_id: u32, b_id: u32) -> bool {
+    let (a, b) = match (gameboard.get_object_by_id(a_id), gameboard.get_object_by_id(b_id)) {
+        (Some(a), Some(b)) => (a, b),
+        _ => return false
+    };
+
+    let from = algebra_basics::get_middle(&a.position, &a.size);
+    let to = algebra_basics::get_middle(&b.position, &b.size);
+    return get_line_of_sight_ignoring(&from, &to, gameboard, &[a_id, b_id]).is_clear();
+}
+
+fn get_line_of_sight_ignoring(from: &Coordinates, to: &Coordinates, gameboard: &Gameboard, ignored: &[u32]) -> LineOfSight {
+    let blocker = gameboard.cast_segment(from, to)
+        .into_iter()
+        .filter(|(id, _)| !ignored.contains(id))
+        .find(|(id, _)| gameboard.get_object_by_id(*id).is_some_and(|object| object.is_opaque()));
+
+    let (id, distance) = match blocker {
+        Some(blocker) => blocker,
+        None => return LineOfSight::Clear
+    };
+
+    let length = algebra_basics::get_distance(from, to);
+    if length == 0.0 {
+        return LineOfSight::Blocked{id, point: from.clone()};
+    }
+
+    let ratio = distance / length;
+    let point = Coordinates::new(from.x + (to.x - from.x) * ratio, from.y + (to.y - from.y) * ratio);
+    return LineOfSight::Blocked{id, point};
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use crate::testing::setup;
+    use crate::algebra_basics::Size;
+    use crate::game_data::game_object::{GameObject, GameObjectType};
+    use crate::game_data::components::Interaction;
+
+    fn setup_gameboard_with_wall() -> (Gameboard, u32) {
+        let mut gameboard = setup::setup_gameboard_with_selectable_object();
+        let wall = GameObject::new(GameObjectType::Static, Coordinates::new(100.0, 0.0), Size::new(20.0, 200.0));
+        let wall_id = gameboard.add_object(wall).unwrap();
+        (gameboard, wall_id)
+    }
+
+    #[test]
+    fn line_of_sight_wall_between_points_blocked_at_wall_edge() {
+        let (gameboard, wall_id) = setup_gameboard_with_wall();
+
+        let result = line_of_sight(&Coordinates::new(25.0, 25.0), &Coordinates::new(225.0, 25.0), &gameboard);
+
+        assert_eq!(result, LineOfSight::Blocked{id: wall_id, point: Coordinates::new(100.0, 25.0)});
+    }
+
+    #[test]
+    fn line_of_sight_only_units_between_points_clear() {
+        let (gameboard, _) = setup_gameboard_with_wall();
+
+        let result = line_of_sight(&Coordinates::new(0.0, 25.0), &Coordinates::new(90.0, 25.0), &gameboard);
+
+        assert_eq!(result.is_clear(), true);
+    }
+
+    #[test]
+    fn line_of_sight_open_door_between_points_clear() {
+        let mut gameboard = Gameboard::new();
+        let door = GameObject::new(GameObjectType::Interactable, Coordinates::new(100.0, 0.0), Size::new(20.0, 200.0));
+        gameboard.add_object(door.with_interaction(Interaction::Door{open: true})).unwrap();
+
+        let result = line_of_sight(&Coordinates::new(25.0, 25.0), &Coordinates::new(225.0, 25.0), &gameboard);
+
+        assert_eq!(result.is_clear(), true);
+    }
+
+    #[test]
+    fn check_if_objects_see_each_other_wall_between_units_false() {
+        let (mut gameboard, _) = setup_gameboard_with_wall();
+        let other_id = gameboard.add_object(setup::setup_selectable_object_at(Coordinates::new(200.0, 0.0))).unwrap();
+
+        assert_eq!(check_if_objects_see_each_other(&gameboard, 0, other_id), false);
+    }
+}
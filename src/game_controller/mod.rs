@@ -1,28 +1,74 @@
 pub mod game_managers;
 pub mod movement_manager;
+pub mod grid_pathfinding;
+pub mod visibility_pathfinding;
+pub mod pathfinder;
+pub mod collision_avoidance;
+pub mod formations;
+pub mod interactions;
+pub mod combat;
+pub mod line_of_sight;
+pub mod fog_of_war;
+pub mod turns;
+pub mod ai;
+pub mod order_queues;
+pub mod rules;
+pub mod snapshot;
 
 pub mod utils {
     use crate::game_controller::game_managers::UserInput;
+    use crate::game_controller::formations::Formation;
     use crate::algebra_basics::Coordinates;
+    use crate::algebra_basics;
     use piston::input::GenericEvent;
     use piston::input::{Button, MouseButton, Key};
+
+    // Cursor travel below which a press and release count as a click rather than a drag.
+    const DRAG_THRESHOLD: f64 = 4.0;
     
     pub struct PistonEventTranslator {
-        pub current_cursor_pos: Coordinates
+        pub current_cursor_pos: Coordinates,
+        pub drag_start: Option<Coordinates>,
+        pub shift_pressed: bool
     }
 
     impl PistonEventTranslator {
+        pub fn get_drag_box(&self) -> Option<(Coordinates, Coordinates)> {
+            if let Some(drag_start) = &self.drag_start {
+                if algebra_basics::get_distance(drag_start, &self.current_cursor_pos) > DRAG_THRESHOLD {
+                    return Some((drag_start.clone(), self.current_cursor_pos.clone()));
+                }
+            }
+
+            return None;
+        }
+
         pub fn piston_event_to_internal_user_input<E: GenericEvent>(&mut self, e: &E) -> UserInput {
             if let Some(cursor_pos) = e.mouse_cursor_args() {
                 self.current_cursor_pos = Coordinates::new(cursor_pos[0], cursor_pos[1]);
             }
 
+            if let Some(args) = e.update_args() {
+                return UserInput::Update(args.dt);
+            }
+
             if let Some(button) = e.press_args() {
                 match button {
                     Button::Mouse(button) => {
                         match button {
-                            MouseButton::Left => return UserInput::LeftMouse(self.current_cursor_pos.clone()),
-                            MouseButton::Right => return UserInput::RightMouse(self.current_cursor_pos.clone()),
+                            MouseButton::Left => {
+                                self.drag_start = Some(self.current_cursor_pos.clone());
+                                if self.shift_pressed {
+                                    return UserInput::ShiftLeftMouse(self.current_cursor_pos.clone());
+                                }
+                                return UserInput::LeftMouse(self.current_cursor_pos.clone());
+                            },
+                            MouseButton::Right => {
+                                if self.shift_pressed {
+                                    return UserInput::ShiftRightMouse(self.current_cursor_pos.clone());
+                                }
+                                return UserInput::RightMouse(self.current_cursor_pos.clone());
+                            },
                             _ => {}
                         }
                         
@@ -31,6 +77,16 @@ pub mod utils {
                         match button {
                             Key::D => return UserInput::D,
                             Key::C => return UserInput::C,
+                            Key::D1 => return UserInput::SelectFormation(Formation::Line),
+                            Key::D2 => return UserInput::SelectFormation(Formation::Column),
+                            Key::D3 => return UserInput::SelectFormation(Formation::Box),
+                            Key::D4 => return UserInput::SelectFormation(Formation::Wedge),
+                            Key::F5 => return UserInput::QuickSave,
+                            Key::F9 => return UserInput::QuickLoad,
+                            Key::Space => return UserInput::EndTurn,
+                            Key::P => return UserInput::Patrol,
+                            Key::S => return UserInput::Stop,
+                            Key::LShift | Key::RShift => self.shift_pressed = true,
                             _ => {}
                         }
                     },
@@ -38,6 +94,21 @@ pub mod utils {
                 }
             }
 
+            if let Some(button) = e.release_args() {
+                match button {
+                    Button::Mouse(MouseButton::Left) => {
+                        let drag_box = self.get_drag_box();
+                        self.drag_start = None;
+
+                        if let Some((start, end)) = drag_box {
+                            return UserInput::SelectionBox{start, end, add: self.shift_pressed};
+                        }
+                    },
+                    Button::Keyboard(Key::LShift) | Button::Keyboard(Key::RShift) => self.shift_pressed = false,
+                    _ => {}
+                }
+            }
+
             return UserInput::NoInputCursorPos(self.current_cursor_pos.clone());
         }
     }
@@ -1,98 +1,257 @@
-use crate::algebra_basics::{Coordinates, Size, Vector, LineEquation};
-use crate::game_data::gameboard::Gameboard;
-use crate::game_data::game_object::{GameObject, GameObjectType};
+use serde::{Serialize, Deserialize};
+
+use crate::algebra_basics::{Coordinates, Vector};
+use crate::algebra_basics;
+
+const PROGRESS_TOLERANCE: f64 = 0.001;
 
+#[derive(Debug, PartialEq, Clone, Serialize, Deserialize)]
 pub struct MovementHandler {
     path: Vec<Coordinates>,
     current_position: Coordinates,
-    current_vector: Vector
+    shortest_remaining_length: f64,
+    stalled_updates: u32 // since the remaining length last dropped below the shortest one so far
 }
 
 impl MovementHandler {
     pub fn start(current_position: Coordinates, destination: Coordinates) -> MovementHandler {
-        let vector = Vector::get_vector(&current_position, &destination);
-        let vector = Vector::to_unit_vector(&vector);
-        let mut path = Vec::new();
-        path.push(destination);
+        MovementHandler::start_with_path(current_position, vec![destination])
+    }
 
-        MovementHandler {
+    pub fn start_with_path(current_position: Coordinates, path: Vec<Coordinates>) -> MovementHandler {
+        let mut movement_handler = MovementHandler {
             path,
             current_position,
-            current_vector: vector
-        }
+            shortest_remaining_length: 0.0,
+            stalled_updates: 0
+        };
+        movement_handler.shortest_remaining_length = movement_handler.get_remaining_length();
+
+        movement_handler
     }
 
-    pub fn poll_movement(&mut self) -> Option<Coordinates> {
+    // Moves by step_length along the path. A waypoint closer than the remaining step is snapped to
+    // and the rest of the step is carried over toward the next one.
+    pub fn poll_movement(&mut self, step_length: f64) -> Option<Coordinates> {
         if self.is_reached() {
             return None;
         }
 
-        self.current_position.x += self.current_vector.x;
-        self.current_position.y += self.current_vector.y;
+        let mut remaining_step = step_length;
+
+        while let Some(waypoint) = self.path.first() {
+            let distance = algebra_basics::get_distance(&self.current_position, waypoint);
+
+            if distance <= remaining_step {
+                self.current_position = self.path.remove(0);
+                remaining_step -= distance;
+                continue;
+            }
+
+            let vector = Vector::get_vector(&self.current_position, waypoint);
+            let unit_vector = Vector::to_unit_vector(&vector);
+            self.current_position.x += unit_vector.x * remaining_step;
+            self.current_position.y += unit_vector.y * remaining_step;
+            break;
+        }
         
         Some(self.current_position.clone())
     }
 
-    fn is_reached(&self) -> bool {
-        if self.path.is_empty() {
-            return true;
-        }
+    pub fn is_reached(&self) -> bool {
+        return self.path.is_empty();
+    }
+
+    pub fn get_destination(&self) -> Option<&Coordinates> {
+        return self.path.last();
+    }
+
+    // Used when the unit ended up somewhere else than it asked for, e.g. after steering around another unit.
+    pub fn set_position(&mut self, position: Coordinates) {
+        self.current_position = position;
+    }
+
+    // Called once per update. Stepping back and forth around an obstacle doesn't count as progress,
+    // only getting closer to the end than ever before does.
+    pub fn record_progress(&mut self) {
+        let remaining_length = self.get_remaining_length();
 
-        if (self.current_position.x as u32) == (self.path[0].x as u32) 
-            && (self.current_position.y as u32) == (self.path[0].y as u32) {
-            return true;
+        if remaining_length < self.shortest_remaining_length - PROGRESS_TOLERANCE {
+            self.shortest_remaining_length = remaining_length;
+            self.stalled_updates = 0;
         }
+        else {
+            self.stalled_updates += 1;
+        }
+    }
 
-        return false;
+    pub fn get_stalled_updates(&self) -> u32 {
+        self.stalled_updates
     }
-}
 
+    pub fn get_current_position(&self) -> &Coordinates {
+        return &self.current_position;
+    }
 
+    pub fn get_path(&self) -> &Vec<Coordinates> {
+        return &self.path;
+    }
 
-pub mod pathfinding {
-use crate::algebra_basics::{Coordinates, LineEquation, Vector, Size, RectangleLineEquations};
-use crate::game_data::gameboard::Gameboard;
-use crate::game_data::game_object::GameObject;
+    // Distance still to walk along the path.
+    pub fn get_remaining_length(&self) -> f64 {
+        let mut length = 0.0;
+        let mut previous = &self.current_position;
+        for waypoint in self.path.iter() {
+            length += algebra_basics::get_distance(previous, waypoint);
+            previous = waypoint;
+        }
 
-    #[derive(PartialEq, Debug)]
-    enum MovementDirection {
-        None,
-        Forward,
-        Backward
+        return length;
     }
 
-    impl MovementDirection {
-        fn get(a: f64, b: f64) -> MovementDirection {
-            if a < b {
-                return MovementDirection::Forward;
+    // Cuts the path so that at most max_length is left to walk, the last waypoint is moved onto the cut.
+    pub fn truncate(&mut self, max_length: f64) {
+        let mut remaining = max_length.max(0.0);
+        let mut previous = self.current_position.clone();
+
+        for index in 0..self.path.len() {
+            let waypoint = self.path[index].clone();
+            let distance = algebra_basics::get_distance(&previous, &waypoint);
+            if distance <= remaining {
+                remaining -= distance;
+                previous = waypoint;
+                continue;
             }
-            else if a > b {
-                return MovementDirection::Backward;
-            }
-            else {
-                return MovementDirection::None;
+
+            self.path.truncate(index);
+            if remaining > 0.0 {
+                let unit_vector = Vector::to_unit_vector(&Vector::get_vector(&previous, &waypoint));
+                self.path.push(Coordinates::new(previous.x + unit_vector.x * remaining, previous.y + unit_vector.y * remaining));
             }
+            return;
         }
     }
+}
+
+
+
+pub mod pathfinding {
+use crate::algebra_basics::{Coordinates, Vector, Size};
+use crate::algebra_basics;
+use crate::game_data::gameboard::Gameboard;
+use crate::game_data::game_object::GameObject;
 
+    // Distance kept between a detour waypoint and the edge of the obstacle it goes around.
+    const OBSTACLE_MARGIN: f64 = 1.0;
+    const MAX_DETOUR_DEPTH: u32 = 8;
+    // Touching an obstacle exactly at the destination doesn't count as crossing it.
+    const DESTINATION_TOLERANCE: f64 = 0.001;
+
+    // Destinations off the board are moved onto its edge.
     pub fn find_path(start: &Coordinates, destination: &Coordinates, gameboard: &Gameboard) -> Vec<Coordinates> {
-        let game_objects = gameboard.get_all_objects();
-        let line_equation = LineEquation::get_line_equation(start, destination);
+        let destination = &gameboard.clamp_position(destination, &Size::new(0.0, 0.0));
+        // Objects covering the start point (e.g. the moving object itself) can't block the way out.
+        let ignored_ids = gameboard.get_objects_at(start);
+
+        find_path_between(start, destination, &ignored_ids, gameboard, 0)
+    }
+
+    fn find_path_between(start: &Coordinates, destination: &Coordinates, ignored_ids: &[u32], gameboard: &Gameboard, depth: u32) -> Vec<Coordinates> {
         let mut points = Vec::new();
 
-        for object in game_objects {
-        
+        if depth < MAX_DETOUR_DEPTH {
+            if let Some(obstacle) = find_first_obstacle(start, destination, ignored_ids, gameboard) {
+                let mut segment_start = start.clone();
+
+                for corner in get_detour(start, destination, obstacle, gameboard) {
+                    points.append(&mut find_path_between(&segment_start, &corner, ignored_ids, gameboard, depth + 1));
+                    segment_start = corner;
+                }
+
+                points.append(&mut find_path_between(&segment_start, destination, ignored_ids, gameboard, depth + 1));
+                return points;
+            }
         }
 
         points.push(destination.clone());
         points
     }
 
-    fn check_if_object_is_an_obstacle(line_equation: &LineEquation,
-        direction_x: &MovementDirection,
-        direction_y: &MovementDirection,
-        object: &GameObject) -> bool {
-        unimplemented!();
+    fn find_first_obstacle<'a>(start: &Coordinates, destination: &Coordinates, ignored_ids: &[u32], gameboard: &'a Gameboard) -> Option<&'a GameObject> {
+        let length = algebra_basics::get_distance(start, destination);
+        if length == 0.0 {
+            return None;
+        }
+
+        return gameboard.cast_segment(start, destination)
+            .into_iter()
+            .filter(|(id, distance)| *distance < length - DESTINATION_TOLERANCE && !ignored_ids.contains(id))
+            .filter_map(|(id, _)| gameboard.get_object_by_id(id))
+            .find(|object| !object.is_passable());
+    }
+
+    // Goes around the obstacle through the corners lying on one side of the start-destination line,
+    // picking the side which stays on the board, or the one which gives the shorter route when both do.
+    fn get_detour(start: &Coordinates, destination: &Coordinates, obstacle: &GameObject, gameboard: &Gameboard) -> Vec<Coordinates> {
+        let direction = Vector::get_vector(start, destination);
+        let (mut left, mut right): (Vec<Coordinates>, Vec<Coordinates>) = get_padded_corners(obstacle)
+            .into_iter()
+            .partition(|corner| {
+                let to_corner = Vector::get_vector(start, corner);
+                direction.x * to_corner.y - direction.y * to_corner.x < 0.0
+            });
+
+        let projection = |corner: &Coordinates| (corner.x - start.x) * direction.x + (corner.y - start.y) * direction.y;
+        left.sort_by(|a, b| projection(a).partial_cmp(&projection(b)).unwrap());
+        right.sort_by(|a, b| projection(a).partial_cmp(&projection(b)).unwrap());
+
+        if left.is_empty() {
+            return right;
+        }
+
+        if right.is_empty() {
+            return left;
+        }
+
+        let is_on_board = |corners: &[Coordinates]| corners.iter()
+            .all(|corner| gameboard.check_if_area_is_within_bounds(corner, &Size::new(0.0, 0.0)));
+        let (left_on_board, right_on_board) = (is_on_board(&left), is_on_board(&right));
+        if left_on_board != right_on_board {
+            return if left_on_board { left } else { right };
+        }
+
+        if get_route_length(start, &left, destination) <= get_route_length(start, &right, destination) {
+            return left;
+        }
+
+        return right;
+    }
+
+    fn get_padded_corners(object: &GameObject) -> Vec<Coordinates> {
+        let position = &object.position;
+        let size = &object.size;
+        let padded_size = Size::new(size.width + 2.0 * OBSTACLE_MARGIN, size.height + 2.0 * OBSTACLE_MARGIN);
+        let x_0 = position.x - OBSTACLE_MARGIN;
+        let y_0 = position.y - OBSTACLE_MARGIN;
+
+        vec![
+            Coordinates::new(x_0, y_0),
+            Coordinates::new(x_0 + padded_size.width, y_0),
+            Coordinates::new(x_0 + padded_size.width, y_0 + padded_size.height),
+            Coordinates::new(x_0, y_0 + padded_size.height)
+        ]
+    }
+
+    fn get_route_length(start: &Coordinates, waypoints: &[Coordinates], destination: &Coordinates) -> f64 {
+        let mut length = 0.0;
+        let mut previous = start;
+
+        for waypoint in waypoints {
+            length += algebra_basics::get_distance(previous, waypoint);
+            previous = waypoint;
+        }
+
+        length + algebra_basics::get_distance(previous, destination)
     }
 
     #[cfg(test)]
@@ -103,20 +262,11 @@ use crate::game_data::game_object::GameObject;
         fn setup_gameboard_with_obstacle(obstacle_coordinates: Coordinates) -> Gameboard {
             let mut gameboard = Gameboard::new();
             let obstacle = GameObject::new(GameObjectType::Static, obstacle_coordinates, Size::new(100.0, 100.0));
-            gameboard.add_object(obstacle);
+            gameboard.add_object(obstacle).unwrap();
     
             return gameboard;
         }
 
-        fn setup_rectangle_line_equations() -> RectangleLineEquations {
-            RectangleLineEquations {
-                x_0: LineEquation::Vertical(5.0),
-                x_1: LineEquation::Vertical(10.0),
-                y_0: LineEquation::Horizontal(5.0),
-                y_1: LineEquation::Horizontal(10.0)
-            }
-        }
-
         #[test]
         fn find_path_obstacle_in_front_of_start_on_x_axis_correct_path_with_3_points_is_calculated() {
             let gameboard = setup_gameboard_with_obstacle(Coordinates::new(150.0, 100.0));
@@ -146,108 +296,94 @@ use crate::game_data::game_object::GameObject;
         }
 
         #[test]
-        fn movmement_direction_a_lower_than_b_forward_returned() {
-            let direction = MovementDirection::get(0.0, 5.0);
-            let expected_direction = MovementDirection::Forward;
-            
-            assert_eq!(direction, expected_direction);
-        }
-    
-        #[test]
-        fn movmement_direction_a_higher_than_b_forward_returned() {
-            let direction = MovementDirection::get(0.5, 0.0);
-            let expected_direction = MovementDirection::Backward;
-            
-            assert_eq!(direction, expected_direction);
-        }
-    
-        #[test]
-        fn movmement_direction_a_same_as_b_forward_returned() {
-            let direction = MovementDirection::get(0.0, 0.0);
-            let expected_direction = MovementDirection::None;
-            
-            assert_eq!(direction, expected_direction);
+        fn find_path_obstacle_in_front_of_start_on_x_axis_path_goes_around_obstacle() {
+            let gameboard = setup_gameboard_with_obstacle(Coordinates::new(150.0, 50.0));
+            let start = Coordinates::new(100.0, 100.0);
+            let path = find_path(&start, &Coordinates::new(300.0, 100.0), &gameboard);
+            let expected = vec![Coordinates::new(149.0, 49.0), Coordinates::new(251.0, 49.0), Coordinates::new(300.0, 100.0)];
+
+            assert_eq!(path, expected);
         }
 
         #[test]
-        fn check_if_object_is_an_obstacle_x_forward_y_none_obstacle_object_provided_returns_true() {
-            let direction_x = MovementDirection::Forward;
-            let direction_y = MovementDirection::None;
-            let game_object = GameObject::new(GameObjectType::Static, Coordinates::new(50.0, 50.0), Size::new(100.0, 100.0));
-            let line = LineEquation::Vertical(75.0);
+        fn find_path_two_obstacles_in_a_row_path_goes_around_both() {
+            let mut gameboard = setup_gameboard_with_obstacle(Coordinates::new(150.0, 50.0));
+            gameboard.add_object(GameObject::new(GameObjectType::Static, Coordinates::new(350.0, 50.0), Size::new(100.0, 100.0))).unwrap();
 
-            let result = check_if_object_is_an_obstacle(&line, &direction_x, &direction_y, &game_object);
+            let path = find_path(&Coordinates::new(100.0, 100.0), &Coordinates::new(500.0, 100.0), &gameboard);
 
-            assert_eq!(result, true);
+            assert_eq!(path.len(), 5);
+            assert_eq!(path.last(), Some(&Coordinates::new(500.0, 100.0)));
         }
 
         #[test]
-        fn check_if_object_is_an_obstacle_x_forward_y_none_non_obstacle_object_provided_returns_false() {
-            let direction_x = MovementDirection::Forward;
-            let direction_y = MovementDirection::None;
-            let game_object = GameObject::new(GameObjectType::Static, Coordinates::new(50.0, 50.0), Size::new(100.0, 100.0));
-            let line = LineEquation::Vertical(0.0);
-
-            let result = check_if_object_is_an_obstacle(&line, &direction_x, &direction_y, &game_object);
+        fn find_path_obstacle_at_board_edge_path_goes_around_on_board_side() {
+            let gameboard = setup_gameboard_with_obstacle(Coordinates::new(150.0, 0.0));
+            let path = find_path(&Coordinates::new(100.0, 10.0), &Coordinates::new(300.0, 10.0), &gameboard);
+            let expected = vec![Coordinates::new(149.0, 101.0), Coordinates::new(251.0, 101.0), Coordinates::new(300.0, 10.0)];
 
-            assert_eq!(result, false);
+            assert_eq!(path, expected);
         }
 
         #[test]
-        fn check_if_object_is_an_obstacle_x_forward_y_forward_obstacle_object_provided_returns_true() {
-            let direction_x = MovementDirection::Forward;
-            let direction_y = MovementDirection::Forward;
-            let game_object = GameObject::new(GameObjectType::Static, Coordinates::new(50.0, 50.0), Size::new(100.0, 100.0));
-            let line = LineEquation::Curve{slope: -1.3125, y_intercept: 400.0};
+        fn find_path_destination_outside_board_path_ends_on_board_edge() {
+            let gameboard = setup_gameboard_with_obstacle(Coordinates::new(150.0, 150.0));
+            let path = find_path(&Coordinates::new(100.0, 100.0), &Coordinates::new(-50.0, 100.0), &gameboard);
 
-            let result = check_if_object_is_an_obstacle(&line, &direction_x, &direction_y, &game_object);
-
-            assert_eq!(result, true);
+            assert_eq!(path, vec![Coordinates::new(0.0, 100.0)]);
         }
 
         #[test]
-        fn check_if_object_is_an_obstacle_x_forward_y_forward_non_obstacle_object_provided_returns_false() {
-            unimplemented!();
-        }
+        fn find_path_no_obstacles_on_the_way_path_with_1_point_is_calculated() {
+            let gameboard = setup_gameboard_with_obstacle(Coordinates::new(150.0, 150.0));
+            let path = find_path(&Coordinates::new(100.0, 100.0), &Coordinates::new(300.0, 100.0), &gameboard);
 
-        #[test]
-        fn check_if_object_is_an_obstacle_x_forward_y_backward_obstacle_object_provided_returns_true() {
-            unimplemented!();
+            assert_eq!(path, vec![Coordinates::new(300.0, 100.0)]);
         }
 
         #[test]
-        fn check_if_object_is_an_obstacle_x_forward_y_backward_non_obstacle_object_provided_returns_false() {
-            unimplemented!();
-        }
+        fn find_first_obstacle_object_on_the_way_returned() {
+            let gameboard = setup_gameboard_with_obstacle(Coordinates::new(50.0, 50.0));
 
-        #[test]
-        fn check_if_object_is_an_obstacle_x_backward_y_none_obstacle_object_provided_returns_true() {
-            unimplemented!();
-        }
+            let result = find_first_obstacle(&Coordinates::new(0.0, 0.0), &Coordinates::new(200.0, 200.0), &[], &gameboard);
 
-        #[test]
-        fn check_if_object_is_an_obstacle_x_backward_y_none_non_obstacle_object_provided_returns_false() {
-            unimplemented!();
+            assert_eq!(result.is_some(), true);
         }
 
         #[test]
-        fn check_if_object_is_an_obstacle_x_backward_y_forward_obstacle_object_provided_returns_true() {
-            unimplemented!();
+        fn find_first_obstacle_object_behind_start_none_returned() {
+            let gameboard = setup_gameboard_with_obstacle(Coordinates::new(50.0, 50.0));
+
+            let result = find_first_obstacle(&Coordinates::new(200.0, 200.0), &Coordinates::new(300.0, 300.0), &[], &gameboard);
+
+            assert_eq!(result.is_none(), true);
         }
 
         #[test]
-        fn check_if_object_is_an_obstacle_x_backward_y_forward_non_obstacle_object_provided_returns_false() {
-            unimplemented!();
+        fn find_first_obstacle_object_off_the_line_none_returned() {
+            let gameboard = setup_gameboard_with_obstacle(Coordinates::new(50.0, 50.0));
+
+            let result = find_first_obstacle(&Coordinates::new(0.0, 300.0), &Coordinates::new(300.0, 300.0), &[], &gameboard);
+
+            assert_eq!(result.is_none(), true);
         }
 
         #[test]
-        fn check_if_object_is_an_obstacle_x_backward_y_backward_obstacle_object_provided_returns_true() {
-            unimplemented!();
+        fn find_first_obstacle_ignored_object_on_the_way_none_returned() {
+            let gameboard = setup_gameboard_with_obstacle(Coordinates::new(50.0, 50.0));
+
+            let result = find_first_obstacle(&Coordinates::new(0.0, 0.0), &Coordinates::new(200.0, 200.0), &[0], &gameboard);
+
+            assert_eq!(result.is_none(), true);
         }
 
         #[test]
-        fn check_if_object_is_an_obstacle_x_backward_y_backwawrd_non_obstacle_object_provided_returns_false() {
-            unimplemented!();
+        fn find_first_obstacle_start_same_as_destination_none_returned() {
+            let gameboard = setup_gameboard_with_obstacle(Coordinates::new(50.0, 50.0));
+
+            let result = find_first_obstacle(&Coordinates::new(75.0, 75.0), &Coordinates::new(75.0, 75.0), &[], &gameboard);
+
+            assert_eq!(result.is_none(), true);
         }
     }
 }
@@ -256,6 +392,26 @@ use crate::game_data::game_object::GameObject;
 mod tests {
 use super::*;
 
+    #[test]
+    fn movement_handler_truncate_path_longer_than_limit_cut_inside_second_segment() {
+        let path = vec![Coordinates::new(30.0, 0.0), Coordinates::new(30.0, 40.0)];
+        let mut movement_handler = MovementHandler::start_with_path(Coordinates::new(0.0, 0.0), path);
+
+        movement_handler.truncate(50.0);
+
+        assert_eq!(movement_handler.get_path(), &vec![Coordinates::new(30.0, 0.0), Coordinates::new(30.0, 20.0)]);
+        assert_eq!(movement_handler.get_remaining_length(), 50.0);
+    }
+
+    #[test]
+    fn movement_handler_truncate_path_shorter_than_limit_unchanged() {
+        let mut movement_handler = MovementHandler::start(Coordinates::new(0.0, 0.0), Coordinates::new(30.0, 40.0));
+
+        movement_handler.truncate(100.0);
+
+        assert_eq!(movement_handler.get_destination(), Some(&Coordinates::new(30.0, 40.0)));
+    }
+
     #[test]
     fn movement_handler_poll_movement_position_changes_correctly() {
         let a = Coordinates::new(0.0, 0.0);
@@ -266,7 +422,19 @@ use super::*;
         let unit_vector = Vector::to_unit_vector(&vector);
 
         let expected = Coordinates::new(a.x + unit_vector.x, a.y + unit_vector.y);
-        let result = movement_handler.poll_movement();
+        let result = movement_handler.poll_movement(1.0);
+
+        assert_eq!(result.unwrap(), expected);
+    }
+
+    #[test]
+    fn movement_handler_poll_movement_position_changes_by_step_length() {
+        let a = Coordinates::new(0.0, 0.0);
+        let b = Coordinates::new(50.0, 0.0);
+        let mut movement_handler = MovementHandler::start(a, b);
+
+        let result = movement_handler.poll_movement(0.25);
+        let expected = Coordinates::new(0.25, 0.0);
 
         assert_eq!(result.unwrap(), expected);
     }
@@ -278,10 +446,78 @@ use super::*;
         let mut movement_handler = MovementHandler::start(a.clone(), b.clone());
         
         for _ in 0..14 {
-            movement_handler.poll_movement();
+            movement_handler.poll_movement(1.0);
+        }
+
+        assert_eq!(movement_handler.poll_movement(1.0).is_some(), true);
+        assert_eq!(movement_handler.poll_movement(1.0).is_none(), true);
+    }
+
+    #[test]
+    fn movement_handler_poll_movement_step_longer_than_remaining_distance_destination_is_snapped_to() {
+        let a = Coordinates::new(0.0, 0.0);
+        let b = Coordinates::new(-10.5, -3.25);
+        let mut movement_handler = MovementHandler::start(a, b.clone());
+
+        let result = movement_handler.poll_movement(100.0);
+
+        assert_eq!(result.unwrap(), b);
+        assert_eq!(movement_handler.is_reached(), true);
+    }
+
+    #[test]
+    fn movement_handler_poll_movement_negative_destination_is_reached() {
+        let a = Coordinates::new(0.0, 0.0);
+        let b = Coordinates::new(-10.5, -3.25);
+        let mut movement_handler = MovementHandler::start(a, b.clone());
+
+        let mut last_position = None;
+        for _ in 0..11 {
+            last_position = movement_handler.poll_movement(1.0);
+        }
+
+        assert_eq!(last_position.unwrap(), b);
+        assert_eq!(movement_handler.poll_movement(1.0).is_none(), true);
+    }
+
+    #[test]
+    fn movement_handler_poll_movement_waypoint_passed_rest_of_step_is_carried_over() {
+        let a = Coordinates::new(0.0, 0.0);
+        let path = vec![Coordinates::new(1.0, 0.0), Coordinates::new(1.0, 10.0)];
+        let mut movement_handler = MovementHandler::start_with_path(a, path);
+
+        let result = movement_handler.poll_movement(3.0);
+        let expected = Coordinates::new(1.0, 2.0);
+
+        assert_eq!(result.unwrap(), expected);
+    }
+
+    #[test]
+    fn movement_handler_poll_movement_next_waypoint_reached_direction_changes() {
+        let a = Coordinates::new(0.0, 0.0);
+        let path = vec![Coordinates::new(10.0, 0.0), Coordinates::new(10.0, 10.0)];
+        let mut movement_handler = MovementHandler::start_with_path(a, path);
+
+        for _ in 0..10 {
+            movement_handler.poll_movement(1.0);
+        }
+
+        let expected = Coordinates::new(10.0, 1.0);
+        let result = movement_handler.poll_movement(1.0);
+
+        assert_eq!(result.unwrap(), expected);
+    }
+
+    #[test]
+    fn movement_handler_poll_movement_returns_none_when_last_waypoint_is_reached() {
+        let a = Coordinates::new(0.0, 0.0);
+        let path = vec![Coordinates::new(5.0, 0.0), Coordinates::new(5.0, 5.0)];
+        let mut movement_handler = MovementHandler::start_with_path(a, path);
+
+        for _ in 0..10 {
+            assert_eq!(movement_handler.poll_movement(1.0).is_some(), true);
         }
 
-        assert_eq!(movement_handler.poll_movement().is_some(), true);
-        assert_eq!(movement_handler.poll_movement().is_none(), true);
+        assert_eq!(movement_handler.poll_movement(1.0).is_none(), true);
     }
-}
\ No newline at end of file
+}
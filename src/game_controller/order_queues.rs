@@ -0,0 +1,260 @@
+use std::collections::VecDeque;
+
+use serde::{Serialize, Deserialize};
+
+use crate::algebra_basics::Coordinates;
+use crate::algebra_basics;
+use crate::game_controller::game_managers::{BasicState, BasicStateContainer, UserInput};
+use crate::game_controller::game_managers;
+use crate::game_controller::turns;
+use crate::game_controller::turns::GameMode;
+
+// How close to a waypoint a unit has to stop for the waypoint to count as visited.
+const ARRIVAL_TOLERANCE: f64 = 5.0;
+
+// Waypoints a unit visits one after another. The first one is where the unit is heading right now.
+#[derive(Debug, PartialEq, Clone, Default, Serialize, Deserialize)]
+pub struct OrderQueue {
+    pub waypoints: VecDeque<Coordinates>,
+    pub looping: bool, // visited waypoints go back to the end of the queue
+    pub last_distance: Option<f64> // from the unit to the first waypoint when it last set off towards it
+}
+
+// Shift+right-click appends a waypoint, patrol makes the selected units loop over their
+// waypoints and the spot they stand on, and stop cancels every order.
+pub fn process_order_queue_input<S>(mut state: S) -> S
+where S: BasicStateContainer {
+    let basic_state = state.get_basic_state();
+
+    if !basic_state.game_mode.check_if_team_can_act(basic_state.local_team) {
+        return state;
+    }
+
+    let selected_ids = game_managers::get_controllable_selected_ids(basic_state);
+
+    match &basic_state.external_event {
+        UserInput::ShiftRightMouse(destination) => {
+            let destination = destination.clone();
+            queue_move(basic_state, &selected_ids, &destination);
+        },
+        UserInput::Patrol => start_patrol(basic_state, &selected_ids),
+        UserInput::Stop => game_managers::order_stop(basic_state, &selected_ids),
+        _ => {}
+    }
+
+    return state;
+}
+
+// Units standing still start moving right away, the others visit the spot after their current orders.
+pub fn queue_move(basic_state: &mut BasicState, ids: &[u32], destination: &Coordinates) {
+    for (id, movement) in game_managers::get_formation_movements(basic_state, ids, destination) {
+        let waypoint = match movement.get_destination() {
+            Some(waypoint) => waypoint.clone(),
+            None => continue
+        };
+
+        let queue = get_queue_with_current_destination(basic_state, id);
+        let idle = queue.waypoints.is_empty();
+        queue.waypoints.push_back(waypoint);
+
+        if idle {
+            basic_state.interaction_orders.remove(&id);
+            basic_state.attack_orders.remove(&id);
+            game_managers::start_movement(basic_state, id, movement);
+        }
+    }
+}
+
+// Units without any waypoints have nowhere to patrol to.
+pub fn start_patrol(basic_state: &mut BasicState, ids: &[u32]) {
+    for id in ids {
+        let position = match basic_state.gameboard.get_object_by_id(*id) {
+            Some(unit) => unit.position.clone(),
+            None => continue
+        };
+
+        let queue = get_queue_with_current_destination(basic_state, *id);
+        if queue.waypoints.is_empty() {
+            basic_state.order_queues.remove(id);
+            continue;
+        }
+
+        queue.waypoints.push_back(position);
+        queue.looping = true;
+    }
+}
+
+// A unit which is already walking somewhere keeps that destination as the first waypoint.
+fn get_queue_with_current_destination(basic_state: &mut BasicState, id: u32) -> &mut OrderQueue {
+    let destination = basic_state.movements.get(&id).and_then(|movement| movement.get_destination()).cloned();
+    let queue = basic_state.order_queues.entry(id).or_default();
+
+    if queue.waypoints.is_empty() {
+        if let Some(destination) = destination {
+            queue.waypoints.push_back(destination);
+        }
+    }
+
+    return queue;
+}
+
+// Units which stopped head for their next waypoint. Units which stopped short of their waypoint,
+// e.g. after running out of action points, try again, unless they got no closer on the last try.
+pub fn process_order_queues<S>(mut state: S) -> S
+where S: BasicStateContainer {
+    let basic_state = state.get_basic_state();
+
+    match basic_state.external_event {
+        UserInput::Update(_) => {},
+        _ => return state
+    }
+
+    let mut ids: Vec<u32> = basic_state.order_queues.keys()
+        .filter(|id| !basic_state.movements.contains_key(id))
+        .cloned()
+        .collect();
+    ids.sort();
+
+    for id in ids {
+        let (position, team) = match (basic_state.gameboard.get_object_by_id(id), basic_state.gameboard.get_team(id)) {
+            (Some(unit), team) => (unit.position.clone(), team),
+            _ => {
+                basic_state.order_queues.remove(&id);
+                continue;
+            }
+        };
+
+        if let Some(team) = team {
+            if !basic_state.game_mode.check_if_team_can_act(team) {
+                continue;
+            }
+        }
+
+        if let GameMode::TurnBased(_) = basic_state.game_mode {
+            if turns::get_affordable_length(&basic_state.gameboard, id) <= 0.0 {
+                continue;
+            }
+        }
+
+        let queue = basic_state.order_queues.get_mut(&id).unwrap();
+        if let Some(waypoint) = queue.waypoints.front() {
+            let distance = algebra_basics::get_distance(&position, waypoint);
+            let unreachable = queue.last_distance.is_some_and(|last_distance| distance >= last_distance);
+
+            if distance <= ARRIVAL_TOLERANCE || unreachable {
+                let visited = queue.waypoints.pop_front().unwrap();
+                if queue.looping {
+                    queue.waypoints.push_back(visited);
+                }
+            }
+        }
+
+        let next = match queue.waypoints.front() {
+            Some(next) => next.clone(),
+            None => {
+                basic_state.order_queues.remove(&id);
+                continue;
+            }
+        };
+        queue.last_distance = Some(algebra_basics::get_distance(&position, &next));
+
+        for (id, movement) in game_managers::get_formation_movements(basic_state, &[id], &next) {
+            game_managers::start_movement(basic_state, id, movement);
+        }
+    }
+
+    return state;
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use crate::testing::setup;
+    use crate::game_controller::game_managers::proces_movement;
+    use crate::game_controller::movement_manager::MovementHandler;
+
+    fn setup_state_with_input(input: UserInput) -> setup::MockMainState {
+        let mut state = setup::setup_game_state_with_one_object();
+        state.basic_state.external_event = input;
+        state
+    }
+
+    #[test]
+    fn process_order_queue_input_shift_right_click_while_moving_waypoint_appended() {
+        let mut state = setup_state_with_input(UserInput::ShiftRightMouse(Coordinates::new(300.0, 0.0)));
+        state.basic_state.movements.insert(0, MovementHandler::start(Coordinates::new(0.0, 0.0), Coordinates::new(100.0, 0.0)));
+
+        let state = process_order_queue_input(state);
+
+        let queue = &state.basic_state.order_queues[&0];
+        assert_eq!(queue.waypoints, vec![Coordinates::new(100.0, 0.0), Coordinates::new(300.0, 0.0)]);
+        assert_eq!(state.basic_state.movements[&0].get_destination(), Some(&Coordinates::new(100.0, 0.0)));
+    }
+
+    #[test]
+    fn process_order_queue_input_shift_right_click_while_idle_movement_starts() {
+        let state = setup_state_with_input(UserInput::ShiftRightMouse(Coordinates::new(300.0, 0.0)));
+
+        let state = process_order_queue_input(state);
+
+        assert_eq!(state.basic_state.movements[&0].get_destination(), Some(&Coordinates::new(300.0, 0.0)));
+    }
+
+    #[test]
+    fn process_order_queues_waypoint_reached_next_one_started() {
+        let mut state = setup_state_with_input(UserInput::Update(0.1));
+        let waypoints = vec![Coordinates::new(0.0, 0.0), Coordinates::new(0.0, 200.0)];
+        state.basic_state.order_queues.insert(0, OrderQueue{waypoints: waypoints.into_iter().collect(), looping: false, last_distance: None});
+
+        let state = process_order_queues(state);
+
+        assert_eq!(state.basic_state.movements[&0].get_destination(), Some(&Coordinates::new(0.0, 200.0)));
+        assert_eq!(state.basic_state.order_queues[&0].waypoints, vec![Coordinates::new(0.0, 200.0)]);
+    }
+
+    #[test]
+    fn process_order_queues_no_progress_on_last_try_waypoint_dropped() {
+        let mut state = setup_state_with_input(UserInput::Update(0.1));
+        let waypoints = vec![Coordinates::new(300.0, 0.0), Coordinates::new(0.0, 200.0)];
+        state.basic_state.order_queues.insert(0, OrderQueue{waypoints: waypoints.into_iter().collect(), looping: false, last_distance: None});
+
+        let mut state = process_order_queues(state);
+        state.basic_state.movements.remove(&0);
+        let state = process_order_queues(state);
+
+        assert_eq!(state.basic_state.order_queues[&0].waypoints, vec![Coordinates::new(0.0, 200.0)]);
+        assert_eq!(state.basic_state.movements[&0].get_destination(), Some(&Coordinates::new(0.0, 200.0)));
+    }
+
+    #[test]
+    fn process_order_queues_patrol_unit_keeps_looping() {
+        let mut state = setup_state_with_input(UserInput::ShiftRightMouse(Coordinates::new(100.0, 0.0)));
+        state = process_order_queue_input(state);
+        state.basic_state.external_event = UserInput::Patrol;
+        state = process_order_queue_input(state);
+
+        // There and back twice.
+        state.basic_state.external_event = UserInput::Update(1.0);
+        for _ in 0..20 {
+            state = proces_movement(state);
+            state = process_order_queues(state);
+        }
+
+        assert_eq!(state.basic_state.order_queues[&0].looping, true);
+        assert_eq!(state.basic_state.movements.contains_key(&0), true);
+    }
+
+    #[test]
+    fn process_order_queue_input_stop_every_order_cancelled() {
+        let mut state = setup_state_with_input(UserInput::ShiftRightMouse(Coordinates::new(100.0, 0.0)));
+        state = process_order_queue_input(state);
+        state.basic_state.attack_orders.insert(0, 5);
+        state.basic_state.external_event = UserInput::Stop;
+
+        let state = process_order_queue_input(state);
+
+        assert_eq!(state.basic_state.movements.is_empty(), true);
+        assert_eq!(state.basic_state.order_queues.is_empty(), true);
+        assert_eq!(state.basic_state.attack_orders.is_empty(), true);
+    }
+}
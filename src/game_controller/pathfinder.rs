@@ -0,0 +1,89 @@
+use std::str::FromStr;
+
+use serde::{Serialize, Deserialize};
+
+use crate::algebra_basics::{Coordinates, Size};
+use crate::game_data::gameboard::Gameboard;
+use crate::game_controller::movement_manager::pathfinding;
+use crate::game_controller::grid_pathfinding;
+use crate::game_controller::visibility_pathfinding;
+
+// Algorithm finding the paths of ordered units, picked from the console with "pathfinder <name>".
+#[derive(PartialEq, Debug, Clone, Copy, Serialize, Deserialize)]
+pub enum Pathfinder {
+    Detour, // goes around each obstacle crossing the straight line
+    Grid, // A* over the cells of a navigation grid
+    Visibility // shortest path over the corners of the obstacles
+}
+
+impl Pathfinder {
+    pub fn find_path(&self, start: &Coordinates, destination: &Coordinates, unit_size: &Size, gameboard: &Gameboard) -> Vec<Coordinates> {
+        match self {
+            Pathfinder::Detour => pathfinding::find_path(start, destination, gameboard),
+            Pathfinder::Grid => grid_pathfinding::find_path(start, destination, unit_size, gameboard),
+            Pathfinder::Visibility => visibility_pathfinding::find_path(start, destination, unit_size, gameboard)
+        }
+    }
+}
+
+impl FromStr for Pathfinder {
+    type Err = ();
+
+    fn from_str(text: &str) -> Result<Pathfinder, ()> {
+        match text {
+            "detour" => Ok(Pathfinder::Detour),
+            "grid" => Ok(Pathfinder::Grid),
+            "visibility" => Ok(Pathfinder::Visibility),
+            _ => Err(())
+        }
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use crate::testing::setup;
+    use crate::algebra_basics;
+    use crate::game_data::game_object::{GameObject, GameObjectType};
+    use crate::game_controller::game_managers::{self, UserInput};
+
+    // The unit at (0,0) has to get around a wall standing between it and the destination.
+    fn move_around_wall(pathfinder: Pathfinder) {
+        let mut state = setup::setup_game_state_with_one_object();
+        let wall = GameObject::new(GameObjectType::Static, Coordinates::new(100.0, 0.0), Size::new(20.0, 150.0));
+        state.basic_state.gameboard.add_object(wall.clone()).unwrap();
+        state.basic_state.pathfinder = pathfinder;
+
+        game_managers::order_move(&mut state.basic_state, &[0], &Coordinates::new(200.0, 0.0));
+        for _ in 0..200 {
+            state.basic_state.external_event = UserInput::Update(0.05);
+            state = game_managers::proces_movement(state);
+
+            let unit = state.basic_state.gameboard.get_object_by_id(0).unwrap();
+            assert_eq!(algebra_basics::check_if_rectangles_overlap(&unit.position, &unit.size, &wall.position, &wall.size), false);
+        }
+
+        assert_eq!(state.basic_state.gameboard.get_object_by_id(0).unwrap().position, Coordinates::new(200.0, 0.0));
+    }
+
+    #[test]
+    fn order_move_detour_pathfinder_unit_walks_around_wall() {
+        move_around_wall(Pathfinder::Detour);
+    }
+
+    #[test]
+    fn order_move_grid_pathfinder_unit_walks_around_wall() {
+        move_around_wall(Pathfinder::Grid);
+    }
+
+    #[test]
+    fn order_move_visibility_pathfinder_unit_walks_around_wall() {
+        move_around_wall(Pathfinder::Visibility);
+    }
+
+    #[test]
+    fn pathfinder_from_str_unknown_name_returns_error() {
+        assert_eq!("grid".parse::<Pathfinder>(), Ok(Pathfinder::Grid));
+        assert_eq!("astar".parse::<Pathfinder>(), Err(()));
+    }
+}
@@ -0,0 +1,203 @@
+use serde::{Serialize, Deserialize};
+
+use crate::algebra_basics;
+use crate::game_data::gameboard::Gameboard;
+use crate::game_data::conditions::{Area, TeamCondition, VictoryCondition};
+use crate::game_data::components::Health;
+use crate::game_controller::game_managers::{BasicStateContainer, GameEvent, UserInput};
+use crate::game_controller::turns::GameMode;
+
+// Exit status of the game, a game closed before it was decided counts as neither result.
+pub const EXIT_VICTORY: i32 = 0;
+pub const EXIT_DEFEAT: i32 = 1;
+pub const EXIT_UNDECIDED: i32 = 2;
+
+#[derive(Debug, PartialEq, Clone, Copy)]
+pub enum GameResult {
+    Victory,
+    Defeat
+}
+
+// Conditions are checked in declaration order, the first one met decides the game.
+// Without any conditions the game never ends.
+#[derive(Debug, PartialEq, Clone, Default, Serialize, Deserialize)]
+pub struct Rules {
+    conditions: Vec<TeamCondition>,
+    hold_times: Vec<f64>, // seconds the area of each condition has been held for, 0 for other conditions
+    winner: Option<u32>
+}
+
+impl Rules {
+    pub fn new(conditions: Vec<TeamCondition>) -> Rules {
+        Rules {
+            hold_times: vec![0.0; conditions.len()],
+            conditions,
+            winner: None
+        }
+    }
+
+    pub fn get_conditions(&self) -> &[TeamCondition] {
+        &self.conditions
+    }
+
+    pub fn get_winner(&self) -> Option<u32> {
+        self.winner
+    }
+
+    // Seen from the given team, None while the game goes on.
+    pub fn get_result(&self, team: u32) -> Option<GameResult> {
+        self.winner.map(|winner| if winner == team { GameResult::Victory } else { GameResult::Defeat })
+    }
+}
+
+pub fn get_exit_code(result: Option<GameResult>) -> i32 {
+    match result {
+        Some(GameResult::Victory) => EXIT_VICTORY,
+        Some(GameResult::Defeat) => EXIT_DEFEAT,
+        None => EXIT_UNDECIDED
+    }
+}
+
+pub fn process_rules<S>(mut state: S) -> S
+where S: BasicStateContainer {
+    let basic_state = state.get_basic_state();
+
+    let time = match basic_state.external_event {
+        UserInput::Update(time) => time,
+        _ => return state
+    };
+
+    if basic_state.rules.winner.is_some() {
+        return state;
+    }
+
+    let gameboard = &basic_state.gameboard;
+    let rules = &mut basic_state.rules;
+    for (i, team_condition) in rules.conditions.iter().enumerate() {
+        let team = team_condition.team;
+        let met = match &team_condition.condition {
+            VictoryCondition::EliminateAllEnemies => gameboard.get_hostile_objects(team)
+                .into_iter()
+                .all(|id| gameboard.get_component::<Health>(id).is_none()),
+            // In the turn-based mode the clock only runs during the turns of the holding team.
+            VictoryCondition::HoldArea{area, seconds} => {
+                if !check_if_area_is_held(gameboard, area, team) {
+                    rules.hold_times[i] = 0.0;
+                }
+                else if basic_state.game_mode.check_if_team_can_act(team) {
+                    rules.hold_times[i] += time;
+                }
+                rules.hold_times[i] >= *seconds
+            },
+            VictoryCondition::SurviveTurns{turns} => match &basic_state.game_mode {
+                GameMode::TurnBased(turn) => turn.number > *turns,
+                GameMode::RealTime => false
+            },
+            VictoryCondition::ReachExit{unit_id, area} => gameboard.get_object_by_id(*unit_id)
+                .is_some_and(|unit| algebra_basics::check_if_rectangles_overlap(&unit.position, &unit.size, &area.position, &area.size))
+        };
+
+        if met {
+            rules.winner = Some(team);
+            basic_state.game_events.push(GameEvent::GameOver{winner: team});
+            break;
+        }
+    }
+
+    return state;
+}
+
+// At least one object of the team and none of another team overlaps the area.
+fn check_if_area_is_held(gameboard: &Gameboard, area: &Area, team: u32) -> bool {
+    let teams: Vec<u32> = gameboard.get_objects_in_area(&area.position, &area.size)
+        .into_iter()
+        .filter_map(|id| gameboard.get_team(id))
+        .collect();
+
+    return teams.contains(&team) && teams.iter().all(|other| *other == team);
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use crate::testing::setup;
+    use crate::algebra_basics::{Coordinates, Size};
+    use crate::game_data::components::Team;
+    use crate::game_controller::turns;
+
+    const ENEMY_TEAM: u32 = setup::LOCAL_TEAM + 1;
+
+    fn setup_state_with_conditions(conditions: Vec<TeamCondition>) -> setup::MockMainState {
+        let mut state = setup::setup_game_state_with_one_object();
+        state.basic_state.rules = Rules::new(conditions);
+        state.basic_state.external_event = UserInput::Update(1.0);
+        state
+    }
+
+    fn setup_area_around_unit() -> Area {
+        Area{position: Coordinates::new(0.0, 0.0), size: Size::new(100.0, 100.0)}
+    }
+
+    #[test]
+    fn process_rules_no_enemy_left_local_team_wins() {
+        let conditions = vec![TeamCondition{team: setup::LOCAL_TEAM, condition: VictoryCondition::EliminateAllEnemies}];
+        let state = setup_state_with_conditions(conditions);
+
+        let state = process_rules(state);
+
+        assert_eq!(state.basic_state.rules.get_result(setup::LOCAL_TEAM), Some(GameResult::Victory));
+        assert_eq!(state.basic_state.game_events, vec![GameEvent::GameOver{winner: setup::LOCAL_TEAM}]);
+    }
+
+    #[test]
+    fn process_rules_area_held_long_enough_team_wins() {
+        let conditions = vec![TeamCondition{team: setup::LOCAL_TEAM, condition: VictoryCondition::HoldArea{area: setup_area_around_unit(), seconds: 2.0}}];
+        let state = setup_state_with_conditions(conditions);
+
+        let state = process_rules(state);
+        assert_eq!(state.basic_state.rules.get_winner(), None);
+        let state = process_rules(state);
+
+        assert_eq!(state.basic_state.rules.get_winner(), Some(setup::LOCAL_TEAM));
+    }
+
+    #[test]
+    fn process_rules_area_held_during_enemy_turn_timer_stopped() {
+        let conditions = vec![TeamCondition{team: setup::LOCAL_TEAM, condition: VictoryCondition::HoldArea{area: setup_area_around_unit(), seconds: 1.0}}];
+        let mut state = setup_state_with_conditions(conditions);
+        let mut enemy = setup::setup_selectable_object_at(Coordinates::new(400.0, 400.0));
+        enemy.components.insert(Team{id: ENEMY_TEAM});
+        state.basic_state.gameboard.add_object(enemy).unwrap();
+        state.basic_state.game_mode = GameMode::turn_based(&mut state.basic_state.gameboard, ENEMY_TEAM);
+        turns::end_turn(&mut state.basic_state);
+
+        let state = process_rules(state);
+
+        assert_eq!(state.basic_state.game_mode.get_active_team(), Some(ENEMY_TEAM));
+        assert_eq!(state.basic_state.rules.get_winner(), None);
+    }
+
+    #[test]
+    fn process_rules_enemy_in_area_area_not_held() {
+        let conditions = vec![TeamCondition{team: setup::LOCAL_TEAM, condition: VictoryCondition::HoldArea{area: setup_area_around_unit(), seconds: 1.0}}];
+        let mut state = setup_state_with_conditions(conditions);
+        let mut enemy = setup::setup_selectable_object_at(Coordinates::new(40.0, 40.0));
+        enemy.components.insert(Team{id: ENEMY_TEAM});
+        state.basic_state.gameboard.add_object(enemy).unwrap();
+
+        let state = process_rules(state);
+
+        assert_eq!(state.basic_state.rules.get_winner(), None);
+    }
+
+    #[test]
+    fn process_rules_enemy_unit_reached_exit_local_team_defeated() {
+        let conditions = vec![TeamCondition{team: ENEMY_TEAM, condition: VictoryCondition::ReachExit{unit_id: 0, area: setup_area_around_unit()}}];
+        let state = setup_state_with_conditions(conditions);
+
+        let state = process_rules(state);
+
+        assert_eq!(state.basic_state.rules.get_result(setup::LOCAL_TEAM), Some(GameResult::Defeat));
+        assert_eq!(get_exit_code(state.basic_state.rules.get_result(setup::LOCAL_TEAM)), EXIT_DEFEAT);
+    }
+}
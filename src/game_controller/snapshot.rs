@@ -0,0 +1,243 @@
+use std::collections::{HashMap, BTreeSet};
+use std::fmt;
+use std::fs;
+use std::io;
+
+use serde::{Serialize, Deserialize};
+
+use crate::game_data::gameboard::Gameboard;
+use crate::game_controller::game_managers::{BasicState, BasicStateContainer, UserInput};
+use crate::game_controller::movement_manager::MovementHandler;
+use crate::game_controller::formations::Formation;
+use crate::game_controller::pathfinder::Pathfinder;
+use crate::game_controller::turns::GameMode;
+use crate::game_controller::order_queues::OrderQueue;
+use crate::game_controller::rules::Rules;
+use crate::game_controller::ai::{AiState, AiStateContainer};
+
+// Bumped whenever the layout of GameSnapshot changes, older files are refused instead of half-loaded.
+pub const SNAPSHOT_VERSION: u32 = 2;
+pub const QUICK_SAVE_PATH: &str = "quicksave.json";
+
+// The debug state, e.g. the debug line, isn't saved, it's rebuilt from the next input anyway.
+#[derive(Serialize, Deserialize)]
+pub struct GameSnapshot {
+    pub version: u32,
+    pub gameboard: Gameboard,
+    pub selected_ids: BTreeSet<u32>,
+    pub movements: HashMap<u32, MovementHandler>,
+    pub interaction_orders: HashMap<u32, u32>,
+    pub attack_orders: HashMap<u32, u32>,
+    pub order_queues: HashMap<u32, OrderQueue>,
+    pub game_mode: GameMode,
+    pub rules: Rules,
+    pub formation: Formation,
+    pub pathfinder: Pathfinder,
+    pub ai_state: AiState
+}
+
+#[derive(Debug)]
+pub enum SnapshotError {
+    Io(io::Error),
+    Format(serde_json::Error),
+    UnsupportedVersion(u64),
+    MissingVersion
+}
+
+impl fmt::Display for SnapshotError {
+    fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
+        match &self {
+            SnapshotError::Io(error) => write!(f, "Could not access snapshot file: {}", error),
+            SnapshotError::Format(error) => write!(f, "Malformed snapshot: {}", error),
+            SnapshotError::UnsupportedVersion(version) => write!(f, "Unsupported snapshot version {}, expected {}", version, SNAPSHOT_VERSION),
+            SnapshotError::MissingVersion => write!(f, "Snapshot has no version"),
+        }
+    }
+}
+
+impl From<io::Error> for SnapshotError {
+    fn from(error: io::Error) -> SnapshotError {
+        SnapshotError::Io(error)
+    }
+}
+
+impl From<serde_json::Error> for SnapshotError {
+    fn from(error: serde_json::Error) -> SnapshotError {
+        SnapshotError::Format(error)
+    }
+}
+
+impl GameSnapshot {
+    pub fn capture(state: &BasicState, ai_state: &AiState) -> GameSnapshot {
+        GameSnapshot {
+            version: SNAPSHOT_VERSION,
+            gameboard: state.gameboard.clone(),
+            selected_ids: state.selected_ids.clone(),
+            movements: state.movements.clone(),
+            interaction_orders: state.interaction_orders.clone(),
+            attack_orders: state.attack_orders.clone(),
+            order_queues: state.order_queues.clone(),
+            game_mode: state.game_mode.clone(),
+            rules: state.rules.clone(),
+            formation: state.formation,
+            pathfinder: state.pathfinder,
+            ai_state: ai_state.clone()
+        }
+    }
+
+    // The AI state is handed back, it's kept outside of the basic state.
+    pub fn restore(self, state: &mut BasicState) -> AiState {
+        state.gameboard = self.gameboard;
+        state.selected_ids = self.selected_ids;
+        state.movements = self.movements;
+        state.interaction_orders = self.interaction_orders;
+        state.attack_orders = self.attack_orders;
+        state.order_queues = self.order_queues;
+        state.game_mode = self.game_mode;
+        state.rules = self.rules;
+        state.formation = self.formation;
+        state.pathfinder = self.pathfinder;
+        return self.ai_state;
+    }
+
+    pub fn serialize(&self) -> Result<String, SnapshotError> {
+        return Ok(serde_json::to_string_pretty(self)?);
+    }
+
+    pub fn deserialize(text: &str) -> Result<GameSnapshot, SnapshotError> {
+        let value: serde_json::Value = serde_json::from_str(text)?;
+
+        match value.get("version").and_then(|version| version.as_u64()) {
+            Some(version) if version == SNAPSHOT_VERSION as u64 => {},
+            Some(version) => return Err(SnapshotError::UnsupportedVersion(version)),
+            None => return Err(SnapshotError::MissingVersion)
+        }
+
+        return Ok(serde_json::from_value(value)?);
+    }
+}
+
+pub fn save_to_file(state: &BasicState, ai_state: &AiState, path: &str) -> Result<(), SnapshotError> {
+    let text = GameSnapshot::capture(state, ai_state).serialize()?;
+    fs::write(path, text)?;
+    return Ok(());
+}
+
+pub fn load_from_file(state: &mut BasicState, path: &str) -> Result<AiState, SnapshotError> {
+    let text = fs::read_to_string(path)?;
+    return Ok(GameSnapshot::deserialize(&text)?.restore(state));
+}
+
+pub fn process_quick_save_and_load<S>(mut state: S) -> S
+where S: BasicStateContainer + AiStateContainer {
+    let result = match state.get_basic_state().external_event {
+        UserInput::QuickSave => {
+            let ai_state = state.get_ai_state().clone();
+            save_to_file(state.get_basic_state(), &ai_state, QUICK_SAVE_PATH)
+        },
+        UserInput::QuickLoad => load_from_file(state.get_basic_state(), QUICK_SAVE_PATH)
+            .map(|ai_state| state.get_ai_state().load(ai_state)),
+        _ => Ok(())
+    };
+
+    if let Err(error) = result {
+        println!("Error: {}", error);
+    }
+
+    return state;
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use crate::testing::setup;
+    use crate::algebra_basics::{Coordinates, Size};
+    use crate::game_data::components::{Team, AiBehaviour, BehaviourMode};
+    use crate::game_controller::ai;
+
+    #[test]
+    fn game_snapshot_serialize_and_deserialize_state_is_restored() {
+        let mut state = setup::setup_game_state_with_one_object();
+        state.basic_state.movements.insert(0, MovementHandler::start_with_path(Coordinates::new(0.0, 0.0), vec![Coordinates::new(10.0, 0.0), Coordinates::new(10.0, 10.0)]));
+        state.basic_state.formation = Formation::Wedge;
+        let text = GameSnapshot::capture(&state.basic_state, &state.ai_state).serialize().unwrap();
+
+        let mut restored = setup::setup_game_state_with_one_object();
+        restored.basic_state.gameboard.remove_object(0).unwrap();
+        restored.basic_state.selected_ids.clear();
+        GameSnapshot::deserialize(&text).unwrap().restore(&mut restored.basic_state);
+
+        let restored = restored.basic_state;
+        assert_eq!(restored.gameboard.get_object_by_id(0).unwrap().position, Coordinates::new(0.0, 0.0));
+        assert_eq!(restored.selected_ids.contains(&0), true);
+        assert_eq!(restored.movements[&0].get_destination(), Some(&Coordinates::new(10.0, 10.0)));
+        assert_eq!(restored.formation, Formation::Wedge);
+    }
+
+    #[test]
+    fn game_snapshot_deserialize_ai_state_is_restored() {
+        let mut state = setup::setup_game_state_with_one_object();
+        let mut unit = setup::setup_selectable_object_at(Coordinates::new(300.0, 300.0));
+        unit.components.insert(Team{id: setup::LOCAL_TEAM + 1});
+        unit.components.insert(AiBehaviour{mode: BehaviourMode::Idle, retreat_below: 0.0});
+        state.basic_state.gameboard.add_object(unit).unwrap();
+        state.ai_state = AiState::new(5);
+        state.basic_state.external_event = UserInput::Update(0.1);
+        let state = ai::process_ai(state);
+        let text = GameSnapshot::capture(&state.basic_state, &state.ai_state).serialize().unwrap();
+
+        let mut restored = setup::setup_game_state_with_one_object();
+        let ai_state = GameSnapshot::deserialize(&text).unwrap().restore(&mut restored.basic_state);
+
+        assert_eq!(ai_state, state.ai_state);
+        assert_ne!(ai_state, AiState::new(5));
+    }
+
+    #[test]
+    fn game_snapshot_deserialize_next_id_is_restored() {
+        let state = setup::setup_game_state_with_one_object();
+        let text = GameSnapshot::capture(&state.basic_state, &state.ai_state).serialize().unwrap();
+
+        let mut gameboard = GameSnapshot::deserialize(&text).unwrap().gameboard;
+        let id = gameboard.add_object(setup::setup_selectable_object()).unwrap();
+
+        assert_eq!(id, 1);
+    }
+
+    #[test]
+    fn game_snapshot_deserialize_bounds_are_restored() {
+        let mut state = setup::setup_game_state_with_one_object();
+        state.basic_state.gameboard = Gameboard::with_bounds(Size::new(800.0, 300.0));
+        let text = GameSnapshot::capture(&state.basic_state, &state.ai_state).serialize().unwrap();
+
+        let gameboard = GameSnapshot::deserialize(&text).unwrap().gameboard;
+
+        assert_eq!((gameboard.get_bounds().width, gameboard.get_bounds().height), (800.0, 300.0));
+    }
+
+    #[test]
+    fn game_snapshot_deserialize_other_version_returns_error() {
+        let state = setup::setup_game_state_with_one_object();
+        let text = GameSnapshot::capture(&state.basic_state, &state.ai_state).serialize().unwrap();
+        let text = text.replace(&format!("\"version\": {}", SNAPSHOT_VERSION), "\"version\": 999");
+
+        let result = GameSnapshot::deserialize(&text);
+
+        match result {
+            Err(SnapshotError::UnsupportedVersion(999)) => {},
+            _ => panic!("expected unsupported version error")
+        }
+    }
+
+    #[test]
+    fn game_snapshot_deserialize_malformed_text_returns_error() {
+        let text = format!("{{\"version\": {}, \"gameboard\": 5}}", SNAPSHOT_VERSION);
+
+        let result = GameSnapshot::deserialize(&text);
+
+        match result {
+            Err(SnapshotError::Format(_)) => {},
+            _ => panic!("expected format error")
+        }
+    }
+}
@@ -0,0 +1,337 @@
+use std::collections::BTreeSet;
+
+use serde::{Serialize, Deserialize};
+
+use crate::algebra_basics::Coordinates;
+use crate::game_data::gameboard::Gameboard;
+use crate::game_data::components::{ActionPoints, ComponentKind};
+use crate::game_controller::game_managers::{BasicState, BasicStateContainer, GameEvent, UserInput};
+use crate::game_controller::game_managers;
+use crate::game_controller::movement_manager::MovementHandler;
+
+// Action points it costs to walk one unit of distance.
+pub const MOVEMENT_COST: f64 = 0.1;
+pub const ATTACK_COST: f64 = 4.0;
+
+#[derive(Debug, PartialEq, Clone, Serialize, Deserialize)]
+pub enum GameMode {
+    RealTime,
+    TurnBased(TurnState)
+}
+
+#[derive(Debug, PartialEq, Clone, Serialize, Deserialize)]
+pub struct TurnState {
+    pub teams: Vec<u32>, // in the order they take turns
+    pub active: usize, // index of the team taking its turn
+    pub number: u32, // counted from 1, grows once every team had its turn
+    #[serde(skip)]
+    pub previews: Vec<PathPreview>,
+    #[serde(skip)]
+    pub preview_cursor: Option<Coordinates> // where the previews were calculated for
+}
+
+// Where a selected unit would walk if the player right-clicked at the cursor, and how far of it is affordable.
+#[derive(Debug, PartialEq, Clone)]
+pub struct PathPreview {
+    pub full: MovementHandler,
+    pub affordable: MovementHandler
+}
+
+impl GameMode {
+    // Teams take turns in ascending id order, the local team always takes part.
+    pub fn turn_based(gameboard: &mut Gameboard, local_team: u32) -> GameMode {
+        let mut teams: BTreeSet<u32> = gameboard.get_objects_with_components(&[ComponentKind::Team])
+            .into_iter()
+            .filter_map(|id| gameboard.get_team(id))
+            .collect();
+        teams.insert(local_team);
+
+        let teams: Vec<u32> = teams.into_iter().collect();
+        refill_action_points(gameboard, teams[0]);
+
+        GameMode::TurnBased(TurnState {
+            teams,
+            active: 0,
+            number: 1,
+            previews: Vec::new(),
+            preview_cursor: None
+        })
+    }
+
+    // None in real time, where every team acts all the time.
+    pub fn get_active_team(&self) -> Option<u32> {
+        match self {
+            GameMode::RealTime => None,
+            GameMode::TurnBased(turn) => Some(turn.teams[turn.active])
+        }
+    }
+
+    pub fn check_if_team_can_act(&self, team: u32) -> bool {
+        match self.get_active_team() {
+            Some(active_team) => active_team == team,
+            None => true
+        }
+    }
+}
+
+pub fn refill_action_points(gameboard: &mut Gameboard, team: u32) {
+    for id in gameboard.get_friendly_objects(team) {
+        if let Some(points) = gameboard.get_component_mut::<ActionPoints>(id) {
+            points.current = points.max;
+        }
+    }
+}
+
+// Nothing is spent when the unit can't afford the cost.
+pub fn spend_action_points(gameboard: &mut Gameboard, id: u32, cost: f64) -> bool {
+    match gameboard.get_component_mut::<ActionPoints>(id) {
+        Some(points) if points.current >= cost => {
+            points.current -= cost;
+            return true;
+        },
+        _ => return false
+    }
+}
+
+// Units without action points can't move at all.
+pub fn get_affordable_length(gameboard: &Gameboard, id: u32) -> f64 {
+    gameboard.get_component::<ActionPoints>(id).map_or(0.0, |points| points.current / MOVEMENT_COST)
+}
+
+// Cuts the path to what the unit can afford and spends the points for the rest of it up front.
+pub fn spend_on_movement(gameboard: &mut Gameboard, id: u32, movement: &mut MovementHandler) {
+    movement.truncate(get_affordable_length(gameboard, id));
+    let cost = movement.get_remaining_length() * MOVEMENT_COST;
+
+    if let Some(points) = gameboard.get_component_mut::<ActionPoints>(id) {
+        points.current = (points.current - cost).max(0.0);
+    }
+}
+
+// Gives back the points spent on the part of the path the unit never walked.
+pub fn refund_movement(gameboard: &mut Gameboard, id: u32, movement: &MovementHandler) {
+    let refund = movement.get_remaining_length() * MOVEMENT_COST;
+
+    if let Some(points) = gameboard.get_component_mut::<ActionPoints>(id) {
+        points.current = (points.current + refund).min(points.max);
+    }
+}
+
+// Passes the turn to the next team, whose units get their action points back.
+pub fn end_turn(basic_state: &mut BasicState) {
+    let turn = match &mut basic_state.game_mode {
+        GameMode::TurnBased(turn) => turn,
+        GameMode::RealTime => return
+    };
+
+    turn.active = (turn.active + 1) % turn.teams.len();
+    if turn.active == 0 {
+        turn.number += 1;
+    }
+    turn.previews.clear();
+    turn.preview_cursor = None;
+
+    let team = turn.teams[turn.active];
+    let number = turn.number;
+    refill_action_points(&mut basic_state.gameboard, team);
+    basic_state.game_events.push(GameEvent::TurnStarted{team, number});
+}
+
+// The player ends the turn of the local team. Other teams end theirs as soon as none of their units moves.
+pub fn process_turns<S>(mut state: S) -> S
+where S: BasicStateContainer {
+    let basic_state = state.get_basic_state();
+
+    let active_team = match basic_state.game_mode.get_active_team() {
+        Some(team) => team,
+        None => return state
+    };
+
+    match basic_state.external_event {
+        UserInput::EndTurn if active_team == basic_state.local_team => end_turn(basic_state),
+        UserInput::Update(_) if active_team != basic_state.local_team => {
+            let gameboard = &basic_state.gameboard;
+            let moving = basic_state.movements.keys().any(|id| gameboard.get_team(*id) == Some(active_team));
+            if !moving {
+                end_turn(basic_state);
+            }
+        },
+        _ => {}
+    }
+
+    return state;
+}
+
+// Recalculated whenever the cursor moves during the turn of the player. Other inputs may change
+// the selection or spend action points, so the next cursor event recalculates them as well.
+pub fn process_path_preview<S>(mut state: S) -> S
+where S: BasicStateContainer {
+    let basic_state = state.get_basic_state();
+
+    let turn = match &mut basic_state.game_mode {
+        GameMode::TurnBased(turn) => turn,
+        GameMode::RealTime => return state
+    };
+
+    let cursor = match &basic_state.external_event {
+        UserInput::NoInputCursorPos(cursor) => cursor.clone(),
+        UserInput::Update(_) => return state,
+        _ => {
+            turn.preview_cursor = None;
+            return state;
+        }
+    };
+
+    if turn.preview_cursor.as_ref() == Some(&cursor) {
+        return state;
+    }
+    turn.preview_cursor = Some(cursor.clone());
+
+    let previews = if basic_state.game_mode.get_active_team() == Some(basic_state.local_team) {
+        let ids = game_managers::get_controllable_selected_ids(basic_state);
+        game_managers::get_formation_movements(basic_state, &ids, &cursor)
+            .into_iter()
+            .map(|(id, full)| {
+                let mut affordable = full.clone();
+                affordable.truncate(get_affordable_length(&basic_state.gameboard, id));
+                PathPreview{full, affordable}
+            })
+            .collect()
+    } else {
+        Vec::new()
+    };
+
+    if let GameMode::TurnBased(turn) = &mut basic_state.game_mode {
+        turn.previews = previews;
+    }
+
+    return state;
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use crate::testing::setup;
+    use crate::game_data::components::Team;
+
+    const ENEMY_TEAM: u32 = setup::LOCAL_TEAM + 1;
+
+    // Unit 0 gets 10 action points, an enemy unit stands far away and the game is turn-based.
+    fn setup_turn_based_state() -> setup::MockMainState {
+        let mut state = setup::setup_game_state_with_one_object();
+        let gameboard = &mut state.basic_state.gameboard;
+        gameboard.get_object_by_id_mut(0).unwrap().components.insert(ActionPoints{current: 0.0, max: 10.0});
+
+        let mut enemy = setup::setup_selectable_object_at(Coordinates::new(400.0, 400.0));
+        enemy.components.insert(Team{id: ENEMY_TEAM});
+        enemy.components.insert(ActionPoints{current: 0.0, max: 10.0});
+        gameboard.add_object(enemy).unwrap();
+
+        state.basic_state.game_mode = GameMode::turn_based(gameboard, setup::LOCAL_TEAM);
+        state
+    }
+
+    #[test]
+    fn turn_based_first_team_action_points_refilled() {
+        let state = setup_turn_based_state();
+
+        assert_eq!(state.basic_state.game_mode.get_active_team(), Some(setup::LOCAL_TEAM));
+        assert_eq!(state.basic_state.gameboard.get_component::<ActionPoints>(0).unwrap().current, 10.0);
+        assert_eq!(state.basic_state.gameboard.get_component::<ActionPoints>(1).unwrap().current, 0.0);
+    }
+
+    #[test]
+    fn process_turns_end_turn_input_next_team_starts() {
+        let mut state = setup_turn_based_state();
+        state.basic_state.external_event = UserInput::EndTurn;
+
+        let state = process_turns(state);
+
+        assert_eq!(state.basic_state.game_mode.get_active_team(), Some(ENEMY_TEAM));
+        assert_eq!(state.basic_state.game_events, vec![GameEvent::TurnStarted{team: ENEMY_TEAM, number: 1}]);
+        assert_eq!(state.basic_state.gameboard.get_component::<ActionPoints>(1).unwrap().current, 10.0);
+    }
+
+    #[test]
+    fn process_turns_other_team_idle_turn_returns_to_player() {
+        let mut state = setup_turn_based_state();
+        state.basic_state.external_event = UserInput::EndTurn;
+        let mut state = process_turns(state);
+        state.basic_state.external_event = UserInput::Update(0.1);
+
+        let state = process_turns(state);
+
+        assert_eq!(state.basic_state.game_mode.get_active_team(), Some(setup::LOCAL_TEAM));
+        assert_eq!(state.basic_state.game_events.last(), Some(&GameEvent::TurnStarted{team: setup::LOCAL_TEAM, number: 2}));
+    }
+
+    #[test]
+    fn spend_on_movement_path_longer_than_affordable_cut_and_points_spent() {
+        let mut state = setup_turn_based_state();
+        let mut movement = MovementHandler::start(Coordinates::new(0.0, 0.0), Coordinates::new(300.0, 0.0));
+
+        spend_on_movement(&mut state.basic_state.gameboard, 0, &mut movement);
+
+        assert_eq!(movement.get_destination(), Some(&Coordinates::new(100.0, 0.0)));
+        assert_eq!(state.basic_state.gameboard.get_component::<ActionPoints>(0).unwrap().current, 0.0);
+    }
+
+    #[test]
+    fn order_stop_movement_half_walked_unwalked_part_refunded() {
+        let mut state = setup_turn_based_state();
+        let movement = MovementHandler::start(Coordinates::new(0.0, 0.0), Coordinates::new(100.0, 0.0));
+        game_managers::start_movement(&mut state.basic_state, 0, movement);
+        state.basic_state.movements.get_mut(&0).unwrap().poll_movement(40.0);
+
+        game_managers::order_stop(&mut state.basic_state, &[0]);
+
+        let points = state.basic_state.gameboard.get_component::<ActionPoints>(0).unwrap().current;
+        assert!((points - 6.0).abs() < 1e-9);
+    }
+
+    #[test]
+    fn start_movement_movement_replaced_only_walked_part_paid() {
+        let mut state = setup_turn_based_state();
+        let movement = MovementHandler::start(Coordinates::new(0.0, 0.0), Coordinates::new(100.0, 0.0));
+        game_managers::start_movement(&mut state.basic_state, 0, movement);
+        state.basic_state.movements.get_mut(&0).unwrap().poll_movement(20.0);
+
+        let movement = MovementHandler::start(Coordinates::new(20.0, 0.0), Coordinates::new(20.0, 30.0));
+        game_managers::start_movement(&mut state.basic_state, 0, movement);
+
+        let points = state.basic_state.gameboard.get_component::<ActionPoints>(0).unwrap().current;
+        assert!((points - 5.0).abs() < 1e-9);
+    }
+
+    #[test]
+    fn process_path_preview_cursor_moved_preview_truncated_to_affordable_length() {
+        let mut state = setup_turn_based_state();
+        state.basic_state.external_event = UserInput::NoInputCursorPos(Coordinates::new(300.0, 0.0));
+
+        let state = process_path_preview(state);
+
+        let previews = match &state.basic_state.game_mode {
+            GameMode::TurnBased(turn) => turn.previews.clone(),
+            GameMode::RealTime => Vec::new()
+        };
+        assert_eq!(previews.len(), 1);
+        assert_eq!(previews[0].affordable.get_remaining_length(), 100.0);
+    }
+
+    #[test]
+    fn process_path_preview_cursor_not_moved_previews_not_recalculated() {
+        let mut state = setup_turn_based_state();
+        state.basic_state.external_event = UserInput::NoInputCursorPos(Coordinates::new(300.0, 0.0));
+        let mut state = process_path_preview(state);
+        if let GameMode::TurnBased(turn) = &mut state.basic_state.game_mode {
+            turn.previews.clear();
+        }
+
+        let state = process_path_preview(state);
+
+        match &state.basic_state.game_mode {
+            GameMode::TurnBased(turn) => assert_eq!(turn.previews.is_empty(), true),
+            GameMode::RealTime => panic!("expected the turn-based mode")
+        }
+    }
+}
@@ -0,0 +1,173 @@
+use crate::algebra_basics::{Coordinates, Size};
+use crate::algebra_basics;
+use crate::game_data::gameboard::Gameboard;
+
+// Distance kept between a corner node and the edge of the obstacle it belongs to.
+const OBSTACLE_MARGIN: f64 = 1.0;
+const START_NODE: usize = 0;
+const DESTINATION_NODE: usize = 1;
+
+// Area a unit of the given size can't put its position into without overlapping the obstacle.
+struct InflatedObstacle {
+    position: Coordinates,
+    size: Size
+}
+
+impl InflatedObstacle {
+    fn contains(&self, point: &Coordinates) -> bool {
+        algebra_basics::check_if_point_is_contained_within_rectangle(point, &self.position, &self.size)
+    }
+
+    fn blocks(&self, a: &Coordinates, b: &Coordinates) -> bool {
+        algebra_basics::check_if_segment_intersects_rectangle(a, b, &self.position, &self.size)
+    }
+
+    fn get_padded_corners(&self) -> Vec<Coordinates> {
+        let x_0 = self.position.x - OBSTACLE_MARGIN;
+        let y_0 = self.position.y - OBSTACLE_MARGIN;
+        let x_1 = self.position.x + self.size.width + OBSTACLE_MARGIN;
+        let y_1 = self.position.y + self.size.height + OBSTACLE_MARGIN;
+
+        vec![
+            Coordinates::new(x_0, y_0),
+            Coordinates::new(x_1, y_0),
+            Coordinates::new(x_1, y_1),
+            Coordinates::new(x_0, y_1)
+        ]
+    }
+}
+
+// Shortest any-angle path over the graph of obstacle corners which can see each other.
+// Destinations off the board are moved onto its edge and corners off the board aren't used.
+pub fn find_path(start: &Coordinates, destination: &Coordinates, unit_size: &Size, gameboard: &Gameboard) -> Vec<Coordinates> {
+    let destination = &gameboard.clamp_position(destination, unit_size);
+    let obstacles: Vec<InflatedObstacle> = gameboard.get_all_objects()
+        .into_iter()
+        .filter(|object| object.is_obstacle())
+        .map(|object| InflatedObstacle {
+            position: Coordinates::new(object.position.x - unit_size.width, object.position.y - unit_size.height),
+            size: Size::new(object.size.width + unit_size.width, object.size.height + unit_size.height)
+        })
+        // A unit already touching an obstacle has to be able to walk away from it.
+        .filter(|obstacle| !obstacle.contains(start))
+        .collect();
+
+    if obstacles.iter().any(|obstacle| obstacle.contains(destination)) {
+        return Vec::new();
+    }
+
+    let mut nodes = vec![start.clone(), destination.clone()];
+    for obstacle in &obstacles {
+        for corner in obstacle.get_padded_corners() {
+            if gameboard.check_if_area_is_within_bounds(&corner, unit_size)
+                && !obstacles.iter().any(|other| other.contains(&corner)) {
+                nodes.push(corner);
+            }
+        }
+    }
+
+    search(&nodes, &obstacles)
+}
+
+fn search(nodes: &[Coordinates], obstacles: &[InflatedObstacle]) -> Vec<Coordinates> {
+    let mut distances = vec![f64::MAX; nodes.len()];
+    let mut previous: Vec<Option<usize>> = vec![None; nodes.len()];
+    let mut visited = vec![false; nodes.len()];
+    distances[START_NODE] = 0.0;
+
+    while let Some(current) = get_closest_unvisited(&distances, &visited) {
+        if current == DESTINATION_NODE {
+            break;
+        }
+
+        visited[current] = true;
+
+        for next in 0..nodes.len() {
+            if visited[next] || obstacles.iter().any(|obstacle| obstacle.blocks(&nodes[current], &nodes[next])) {
+                continue;
+            }
+
+            let distance = distances[current] + algebra_basics::get_distance(&nodes[current], &nodes[next]);
+            if distance < distances[next] {
+                distances[next] = distance;
+                previous[next] = Some(current);
+            }
+        }
+    }
+
+    if distances[DESTINATION_NODE] == f64::MAX {
+        return Vec::new();
+    }
+
+    let mut path = Vec::new();
+    let mut node = DESTINATION_NODE;
+    while node != START_NODE {
+        path.push(nodes[node].clone());
+        node = previous[node].unwrap();
+    }
+
+    path.reverse();
+    path
+}
+
+fn get_closest_unvisited(distances: &[f64], visited: &[bool]) -> Option<usize> {
+    distances.iter()
+        .enumerate()
+        .filter(|(index, distance)| !visited[*index] && **distance < f64::MAX)
+        .min_by(|a, b| a.1.partial_cmp(b.1).unwrap())
+        .map(|(index, _)| index)
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use crate::game_data::game_object::{GameObject, GameObjectType};
+
+    fn setup_gameboard_with_wall(object_type: GameObjectType) -> Gameboard {
+        let mut gameboard = Gameboard::new();
+        let wall = GameObject::new(object_type, Coordinates::new(100.0, 0.0), Size::new(20.0, 150.0));
+        gameboard.add_object(wall).unwrap();
+
+        return gameboard;
+    }
+
+    #[test]
+    fn find_path_no_obstacles_path_with_1_point_is_calculated() {
+        let gameboard = Gameboard::new();
+        let destination = Coordinates::new(200.0, 0.0);
+
+        let path = find_path(&Coordinates::new(0.0, 0.0), &destination, &Size::new(10.0, 10.0), &gameboard);
+
+        assert_eq!(path, vec![destination]);
+    }
+
+    #[test]
+    fn find_path_static_wall_between_start_and_destination_shortest_path_around_corners_is_calculated() {
+        let gameboard = setup_gameboard_with_wall(GameObjectType::Static);
+
+        let path = find_path(&Coordinates::new(20.0, 20.0), &Coordinates::new(200.0, 20.0), &Size::new(10.0, 10.0), &gameboard);
+        // Going over the wall would leave the board, so the path goes under it.
+        let expected = vec![Coordinates::new(89.0, 151.0), Coordinates::new(121.0, 151.0), Coordinates::new(200.0, 20.0)];
+
+        assert_eq!(path, expected);
+    }
+
+    #[test]
+    fn find_path_selectable_object_between_start_and_destination_path_with_1_point_is_calculated() {
+        let gameboard = setup_gameboard_with_wall(GameObjectType::Selectable);
+        let destination = Coordinates::new(200.0, 20.0);
+
+        let path = find_path(&Coordinates::new(20.0, 20.0), &destination, &Size::new(10.0, 10.0), &gameboard);
+
+        assert_eq!(path, vec![destination]);
+    }
+
+    #[test]
+    fn find_path_destination_inside_inflated_obstacle_returns_empty_path() {
+        let gameboard = setup_gameboard_with_wall(GameObjectType::Interactable);
+
+        let path = find_path(&Coordinates::new(20.0, 20.0), &Coordinates::new(95.0, 20.0), &Size::new(10.0, 10.0), &gameboard);
+
+        assert_eq!(path.is_empty(), true);
+    }
+}
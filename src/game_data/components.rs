@@ -0,0 +1,270 @@
+use std::collections::BTreeMap;
+
+use serde::{Serialize, Deserialize};
+
+use crate::algebra_basics::Coordinates;
+
+// Game specific data attached to objects. A new kind of data is added as a struct implementing
+// Component, with a matching variant in both ComponentKind and ComponentData.
+pub trait Component: Sized {
+    const KIND: ComponentKind;
+
+    fn into_data(self) -> ComponentData;
+    fn from_data(data: &ComponentData) -> Option<&Self>;
+    fn from_data_mut(data: &mut ComponentData) -> Option<&mut Self>;
+}
+
+#[derive(Debug, PartialEq, Eq, PartialOrd, Ord, Hash, Clone, Copy, Serialize, Deserialize)]
+pub enum ComponentKind {
+    Inventory,
+    Team,
+    Health,
+    Armor,
+    Weapon,
+    Sight,
+    ActionPoints,
+    AiBehaviour,
+    Speed,
+    Interaction,
+    Attributes
+}
+
+#[derive(Debug, PartialEq, Clone, Serialize, Deserialize)]
+pub enum ComponentData {
+    Inventory(Inventory),
+    Team(Team),
+    Health(Health),
+    Armor(Armor),
+    Weapon(Weapon),
+    Sight(Sight),
+    ActionPoints(ActionPoints),
+    AiBehaviour(AiBehaviour),
+    Speed(Speed),
+    Interaction(Interaction),
+    Attributes(Attributes)
+}
+
+macro_rules! impl_component {
+    ($name:ident) => {
+        impl Component for $name {
+            const KIND: ComponentKind = ComponentKind::$name;
+
+            fn into_data(self) -> ComponentData {
+                ComponentData::$name(self)
+            }
+
+            fn from_data(data: &ComponentData) -> Option<&Self> {
+                match data {
+                    ComponentData::$name(component) => Some(component),
+                    _ => None
+                }
+            }
+
+            fn from_data_mut(data: &mut ComponentData) -> Option<&mut Self> {
+                match data {
+                    ComponentData::$name(component) => Some(component),
+                    _ => None
+                }
+            }
+        }
+    };
+}
+
+impl ComponentData {
+    pub fn get_kind(&self) -> ComponentKind {
+        match self {
+            ComponentData::Inventory(_) => ComponentKind::Inventory,
+            ComponentData::Team(_) => ComponentKind::Team,
+            ComponentData::Health(_) => ComponentKind::Health,
+            ComponentData::Armor(_) => ComponentKind::Armor,
+            ComponentData::Weapon(_) => ComponentKind::Weapon,
+            ComponentData::Sight(_) => ComponentKind::Sight,
+            ComponentData::ActionPoints(_) => ComponentKind::ActionPoints,
+            ComponentData::AiBehaviour(_) => ComponentKind::AiBehaviour,
+            ComponentData::Speed(_) => ComponentKind::Speed,
+            ComponentData::Interaction(_) => ComponentKind::Interaction,
+            ComponentData::Attributes(_) => ComponentKind::Attributes
+        }
+    }
+}
+
+// Items picked up by the unit.
+#[derive(Debug, PartialEq, Clone, Default, Serialize, Deserialize)]
+pub struct Inventory {
+    pub items: Vec<String>
+}
+
+impl_component!(Inventory);
+
+// Side the object fights for. Objects without a team belong to nobody.
+#[derive(Debug, PartialEq, Eq, Clone, Copy, Serialize, Deserialize)]
+pub struct Team {
+    pub id: u32
+}
+
+impl_component!(Team);
+
+// The object dies when current drops to 0.
+#[derive(Debug, PartialEq, Clone, Copy, Serialize, Deserialize)]
+pub struct Health {
+    pub current: f64,
+    pub max: f64
+}
+
+impl_component!(Health);
+
+// Subtracted from the damage per second of every weapon hitting the object.
+#[derive(Debug, PartialEq, Clone, Copy, Serialize, Deserialize)]
+pub struct Armor {
+    pub value: f64
+}
+
+impl_component!(Armor);
+
+#[derive(Debug, PartialEq, Clone, Copy, Serialize, Deserialize)]
+pub struct Weapon {
+    pub damage: f64, // per second
+    pub range: f64 // largest gap between the attacker and its target
+}
+
+impl_component!(Weapon);
+
+// Units with sight reveal the board around them for their team.
+#[derive(Debug, PartialEq, Clone, Copy, Serialize, Deserialize)]
+pub struct Sight {
+    pub radius: f64 // measured from the middle of the unit
+}
+
+impl_component!(Sight);
+
+// Spent on moving and attacking in the turn-based mode, refilled at the start of every turn of the team.
+#[derive(Debug, PartialEq, Clone, Copy, Serialize, Deserialize)]
+pub struct ActionPoints {
+    pub current: f64,
+    pub max: f64
+}
+
+impl_component!(ActionPoints);
+
+// Objects without it move at the default speed.
+#[derive(Debug, PartialEq, Clone, Copy, Serialize, Deserialize)]
+pub struct Speed {
+    pub value: f64 // units per second
+}
+
+impl_component!(Speed);
+
+// Units with a behaviour are controlled by the computer, unless they belong to the local team.
+#[derive(Debug, PartialEq, Clone, Serialize, Deserialize)]
+pub struct AiBehaviour {
+    pub mode: BehaviourMode,
+    #[serde(default)]
+    pub retreat_below: f64 // share of the max health under which the unit runs from enemies, 0 never
+}
+
+#[derive(Debug, PartialEq, Clone, Serialize, Deserialize)]
+pub enum BehaviourMode {
+    Idle,
+    Guard{post: Coordinates, radius: f64}, // attacks enemies coming within the radius of the post
+    Patrol{waypoints: Vec<Coordinates>, #[serde(default)] next: usize},
+    Chase, // attacks the nearest enemy it sees
+    Capture, // takes capture points its team doesn't hold, fighting the enemies it sees on the way
+    Custom{name: String} // behaviour registered under the name
+}
+
+impl_component!(AiBehaviour);
+
+// What happens when a unit interacts with the object, only used by Interactable objects.
+#[derive(Debug, PartialEq, Clone, Serialize, Deserialize)]
+pub enum Interaction {
+    Door{open: bool},
+    Pickup{item: String},
+    CapturePoint{captured_by: Option<u32>}, // id of the unit which took it last
+    Switch{targets: Vec<u32>} // ids of the objects toggled by the switch
+}
+
+impl_component!(Interaction);
+
+// Free-form data declared in scenario files.
+#[derive(Debug, PartialEq, Clone, Default, Serialize, Deserialize)]
+pub struct Attributes {
+    pub values: BTreeMap<String, String>
+}
+
+impl_component!(Attributes);
+
+// At most one component of each kind per object. Saved as a plain list of components.
+#[derive(Debug, PartialEq, Clone, Default, Serialize, Deserialize)]
+#[serde(from = "Vec<ComponentData>", into = "Vec<ComponentData>")]
+pub struct Components {
+    components: BTreeMap<ComponentKind, ComponentData>
+}
+
+impl From<Vec<ComponentData>> for Components {
+    fn from(list: Vec<ComponentData>) -> Components {
+        let mut components = Components::default();
+        for data in list {
+            components.insert_data(data);
+        }
+
+        components
+    }
+}
+
+impl From<Components> for Vec<ComponentData> {
+    fn from(components: Components) -> Vec<ComponentData> {
+        components.components.into_values().collect()
+    }
+}
+
+impl Components {
+    pub fn get<C: Component>(&self) -> Option<&C> {
+        self.components.get(&C::KIND).and_then(C::from_data)
+    }
+
+    pub fn get_mut<C: Component>(&mut self) -> Option<&mut C> {
+        self.components.get_mut(&C::KIND).and_then(C::from_data_mut)
+    }
+
+    // Replaces the component of the same kind, if there was one.
+    pub fn insert<C: Component>(&mut self, component: C) {
+        self.insert_data(component.into_data());
+    }
+
+    pub fn insert_data(&mut self, data: ComponentData) {
+        self.components.insert(data.get_kind(), data);
+    }
+
+    pub fn remove(&mut self, kind: ComponentKind) -> Option<ComponentData> {
+        self.components.remove(&kind)
+    }
+
+    pub fn has_all(&self, kinds: &[ComponentKind]) -> bool {
+        kinds.iter().all(|kind| self.components.contains_key(kind))
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn components_insert_component_is_returned_by_get() {
+        let mut components = Components::default();
+        components.insert(Inventory{items: vec!["key".to_string()]});
+
+        assert_eq!(components.get::<Inventory>().map(|inventory| inventory.items.len()), Some(1));
+        assert_eq!(components.has_all(&[ComponentKind::Inventory]), true);
+    }
+
+    #[test]
+    fn components_serialize_and_deserialize_components_are_restored() {
+        let mut components = Components::default();
+        components.insert(Inventory{items: vec!["key".to_string()]});
+        let text = serde_json::to_string(&components).unwrap();
+
+        let restored: Components = serde_json::from_str(&text).unwrap();
+
+        assert_eq!(restored, components);
+    }
+}
@@ -0,0 +1,26 @@
+use serde::{Serialize, Deserialize};
+
+use crate::algebra_basics::{Coordinates, Size};
+
+// Part of the board, e.g. an exit zone or a capture area.
+#[derive(Debug, PartialEq, Clone, Serialize, Deserialize)]
+#[serde(deny_unknown_fields)]
+pub struct Area {
+    pub position: Coordinates,
+    pub size: Size
+}
+
+#[derive(Debug, PartialEq, Clone, Serialize, Deserialize)]
+pub enum VictoryCondition {
+    EliminateAllEnemies, // no unit of another team with health is left
+    HoldArea{area: Area, seconds: f64}, // units of the team stand in the area, with no enemy inside
+    SurviveTurns{turns: u32}, // only in the turn-based mode
+    ReachExit{unit_id: u32, area: Area}
+}
+
+// The team wins as soon as the condition is met.
+#[derive(Debug, PartialEq, Clone, Serialize, Deserialize)]
+pub struct TeamCondition {
+    pub team: u32,
+    pub condition: VictoryCondition
+}
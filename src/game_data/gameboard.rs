@@ -1,68 +1,390 @@
-use std::collections::HashMap;
-use std::cmp::Eq;
-use std::boxed::Box;
+use std::collections::{HashMap, BTreeMap};
+use std::fmt;
 
-use crate::game_data::game_object;
-use crate::game_data::game_object::GameObject;
-use crate::game_data::game_object::GameObjectType;
+use serde::{Serialize, Deserialize};
+
+use crate::game_data::game_object::{GameObject, GameObjectType};
+use crate::game_data::spatial_index::SpatialIndex;
+use crate::game_data::visibility::VisibilityMap;
+use crate::game_data::components::{Component, ComponentKind, Interaction, Team};
 use crate::algebra_basics::{Coordinates, Size};
 use crate::algebra_basics;
 
+// Size of the game window, used when no other bounds are given.
+pub const DEFAULT_BOUNDS: Size = Size { width: 512.0, height: 512.0 };
+
 pub enum GameboardObjectOperation {
-    Move(Coordinates)
+    Move(Coordinates),
+    SetInteraction(Interaction)
+}
+
+#[derive(PartialEq, Debug)]
+pub enum GameboardError {
+    UnknownId(u32),
+    OutOfBounds(Coordinates),
+    Overlapping(u32), // id of the object in the way
+    ImmovableType(GameObjectType)
 }
 
+impl fmt::Display for GameboardError {
+    fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
+        match &self {
+            GameboardError::UnknownId(id) => write!(f, "There is no object with id {}", id),
+            GameboardError::OutOfBounds(position) => write!(f, "Position x: {}, y: {} is outside the board", position.x, position.y),
+            GameboardError::Overlapping(id) => write!(f, "Position is taken by object {}", id),
+            GameboardError::ImmovableType(object_type) => write!(f, "{:?} objects can't be moved", object_type),
+        }
+    }
+}
+
+// The board spans from (0, 0) to its bounds, every object has to lie entirely within it.
+// The spatial index is rebuilt on load, only objects, bounds, visibility and the id counter are saved.
+#[derive(Clone, Serialize, Deserialize)]
+#[serde(from = "GameboardData", into = "GameboardData")]
 pub struct Gameboard {
-    pub game_objects: HashMap<u32, GameObject>,
-    next_id: u32
+    game_objects: HashMap<u32, GameObject>,
+    next_id: u32,
+    bounds: Size,
+    visibility: BTreeMap<u32, VisibilityMap>, // team id to what the team knows about the board
+    spatial_index: SpatialIndex
+}
+
+#[derive(Serialize, Deserialize)]
+struct GameboardData {
+    game_objects: HashMap<u32, GameObject>,
+    next_id: u32,
+    bounds: Size,
+    #[serde(default)]
+    visibility: BTreeMap<u32, VisibilityMap>
+}
+
+impl From<GameboardData> for Gameboard {
+    fn from(data: GameboardData) -> Gameboard {
+        let mut spatial_index = SpatialIndex::default();
+        for (id, object) in data.game_objects.iter() {
+            spatial_index.insert(*id, &object.position, &object.size);
+        }
+
+        Gameboard {
+            game_objects: data.game_objects,
+            next_id: data.next_id,
+            bounds: data.bounds,
+            visibility: data.visibility,
+            spatial_index
+        }
+    }
+}
+
+impl From<Gameboard> for GameboardData {
+    fn from(gameboard: Gameboard) -> GameboardData {
+        GameboardData {
+            game_objects: gameboard.game_objects,
+            next_id: gameboard.next_id,
+            bounds: gameboard.bounds,
+            visibility: gameboard.visibility
+        }
+    }
+}
+
+impl Default for Gameboard {
+    fn default() -> Gameboard {
+        Gameboard::new()
+    }
 }
 
 impl Gameboard {
     pub fn new() -> Gameboard {
+        Gameboard::with_bounds(DEFAULT_BOUNDS)
+    }
+
+    pub fn with_bounds(bounds: Size) -> Gameboard {
         Gameboard {
             game_objects: HashMap::new(),
-            next_id: 0
+            next_id: 0,
+            bounds,
+            visibility: BTreeMap::new(),
+            spatial_index: SpatialIndex::default()
         }
     }
 
-    pub fn add_object(&mut self, object: GameObject) -> u32 {
+    pub fn get_bounds(&self) -> &Size {
+        &self.bounds
+    }
+
+    pub fn check_if_area_is_within_bounds(&self, position: &Coordinates, size: &Size) -> bool {
+        position.x >= 0.0 && position.y >= 0.0
+            && position.x + size.width <= self.bounds.width
+            && position.y + size.height <= self.bounds.height
+    }
+
+    // Closest position at which an object of the given size lies within the board.
+    // Objects larger than the board are pinned to its upper left corner.
+    pub fn clamp_position(&self, position: &Coordinates, size: &Size) -> Coordinates {
+        let max_x = (self.bounds.width - size.width).max(0.0);
+        let max_y = (self.bounds.height - size.height).max(0.0);
+
+        Coordinates::new(position.x.max(0.0).min(max_x), position.y.max(0.0).min(max_y))
+    }
+
+    pub fn add_object(&mut self, object: GameObject) -> Result<u32, GameboardError> {
+        if !self.check_if_area_is_within_bounds(&object.position, &object.size) {
+            return Err(GameboardError::OutOfBounds(object.position));
+        }
+
+        if let Some(other_id) = self.get_blocking_object(&object, &object.position, None) {
+            return Err(GameboardError::Overlapping(other_id));
+        }
+
+        self.spatial_index.insert(self.next_id, &object.position, &object.size);
         self.game_objects.insert(self.next_id, object);
 
         let current_id = self.next_id;
         self.next_id +=1;
 
-        return current_id;
+        return Ok(current_id);
     }
 
-    pub fn remove_object(&mut self, id: u32) {
-        self.game_objects.remove(&id);
+    pub fn remove_object(&mut self, id: u32) -> Result<(), GameboardError> {
+        let object = self.game_objects.remove(&id).ok_or(GameboardError::UnknownId(id))?;
+        self.spatial_index.remove(id, &object.position, &object.size);
+
+        return Ok(());
     }
 
     pub fn get_object_by_id(&self, id: u32) -> Option<&GameObject> {
         self.game_objects.get(&id)
     }
 
+    // Position and size have to be changed with execute_operation, otherwise the spatial index goes stale.
+    pub fn get_object_by_id_mut(&mut self, id: u32) -> Option<&mut GameObject> {
+        self.game_objects.get_mut(&id)
+    }
+
     pub fn querry_object<Q>(&self, querry: Q) -> Option<(&u32, &GameObject)> 
     where for<'r> Q: FnMut(&'r (&u32, &GameObject)) -> bool {
         self.game_objects.iter().find(querry)
     }
 
-    pub fn execute_operation(&mut self, id: u32, operation: GameboardObjectOperation) {
-        if let Some(object) = self.game_objects.get_mut(&id) {
-            match operation {
-                GameboardObjectOperation::Move(new_position) => object.position = new_position
+    pub fn execute_operation(&mut self, id: u32, operation: GameboardObjectOperation) -> Result<(), GameboardError> {
+        let object = self.game_objects.get(&id).ok_or(GameboardError::UnknownId(id))?;
+
+        match operation {
+            GameboardObjectOperation::Move(new_position) => {
+                if object.object_type == GameObjectType::Static {
+                    return Err(GameboardError::ImmovableType(GameObjectType::Static));
+                }
+
+                if !self.check_if_area_is_within_bounds(&new_position, &object.size) {
+                    return Err(GameboardError::OutOfBounds(new_position));
+                }
+
+                if let Some(other_id) = self.get_blocking_object(object, &new_position, Some(id)) {
+                    return Err(GameboardError::Overlapping(other_id));
+                }
+
+                self.spatial_index.remove(id, &object.position, &object.size);
+                self.spatial_index.insert(id, &new_position, &object.size);
+                if let Some(object) = self.game_objects.get_mut(&id) {
+                    object.position = new_position;
+                }
+            },
+            GameboardObjectOperation::SetInteraction(interaction) => {
+                let mut changed = object.clone();
+                changed.components.insert(interaction);
+
+                // An object which stops being passable can't shut on whatever stands in it.
+                if changed.is_obstacle() {
+                    let standing_id = self.get_objects_in_area(&object.position, &object.size)
+                        .into_iter()
+                        .find(|other_id| *other_id != id);
+                    if let Some(other_id) = standing_id {
+                        return Err(GameboardError::Overlapping(other_id));
+                    }
+                }
+
+                self.game_objects.insert(id, changed);
             }
         }
+
+        return Ok(());
+    }
+
+    // Units may overlap each other, but nothing may overlap an obstacle. A moving object isn't blocked
+    // by obstacles it already overlaps, so it can get away from them.
+    fn get_blocking_object(&self, object: &GameObject, position: &Coordinates, moving_id: Option<u32>) -> Option<u32> {
+        return self.get_objects_in_area(position, &object.size)
+            .into_iter()
+            .filter(|other_id| Some(*other_id) != moving_id)
+            .find(|other_id| {
+                let other = &self.game_objects[other_id];
+                let is_already_overlapping = moving_id.is_some()
+                    && algebra_basics::check_if_rectangles_overlap(&object.position, &object.size, &other.position, &other.size);
+
+                (object.is_obstacle() || other.is_obstacle()) && !is_already_overlapping
+            });
     }
 
     pub fn get_all_objects(&self) -> Vec<&GameObject> {
         let mut objects = Vec::new();
-        for (_, object) in &self.game_objects {
+        for object in self.game_objects.values() {
             objects.push(object);
         }
 
         return objects;
     }
+
+    pub fn iter(&self) -> impl Iterator<Item = (&u32, &GameObject)> {
+        self.game_objects.iter()
+    }
+
+    // Ids of objects having every one of the components, in ascending order.
+    pub fn get_objects_with_components(&self, kinds: &[ComponentKind]) -> Vec<u32> {
+        let mut ids: Vec<u32> = self.game_objects.iter()
+            .filter(|(_, object)| object.components.has_all(kinds))
+            .map(|(id, _)| *id)
+            .collect();
+
+        ids.sort();
+        return ids;
+    }
+
+    pub fn get_component<C: Component>(&self, id: u32) -> Option<&C> {
+        self.game_objects.get(&id)?.components.get::<C>()
+    }
+
+    pub fn get_component_mut<C: Component>(&mut self, id: u32) -> Option<&mut C> {
+        self.game_objects.get_mut(&id)?.components.get_mut::<C>()
+    }
+
+    pub fn get_team(&self, id: u32) -> Option<u32> {
+        self.get_component::<Team>(id).map(|team| team.id)
+    }
+
+    // Ids of objects of the team, in ascending order.
+    pub fn get_friendly_objects(&self, team: u32) -> Vec<u32> {
+        return self.get_objects_with_components(&[ComponentKind::Team])
+            .into_iter()
+            .filter(|id| self.get_team(*id) == Some(team))
+            .collect();
+    }
+
+    // Ids of objects of every other team, in ascending order. Objects without a team aren't hostile.
+    pub fn get_hostile_objects(&self, team: u32) -> Vec<u32> {
+        return self.get_objects_with_components(&[ComponentKind::Team])
+            .into_iter()
+            .filter(|id| self.get_team(*id) != Some(team))
+            .collect();
+    }
+
+    pub fn check_if_hostile(&self, id: u32, other_id: u32) -> bool {
+        match (self.get_team(id), self.get_team(other_id)) {
+            (Some(team), Some(other_team)) => team != other_team,
+            _ => false
+        }
+    }
+
+    pub fn get_visibility(&self, team: u32) -> Option<&VisibilityMap> {
+        self.visibility.get(&team)
+    }
+
+    pub fn set_visibility(&mut self, team: u32, map: VisibilityMap) {
+        self.visibility.insert(team, map);
+    }
+
+    // Only objects of other teams can be hidden, and only from teams which have a visibility map.
+    pub fn check_if_visible_to(&self, team: u32, id: u32) -> bool {
+        let object = match self.game_objects.get(&id) {
+            Some(object) => object,
+            None => return false
+        };
+
+        match (object.components.get::<Team>(), self.visibility.get(&team)) {
+            (Some(object_team), Some(map)) if object_team.id != team => map.check_if_area_is_visible(&object.position, &object.size),
+            _ => true
+        }
+    }
+
+    // Ids of objects the team can see, in ascending order.
+    pub fn get_objects_visible_to(&self, team: u32) -> Vec<u32> {
+        let mut ids: Vec<u32> = self.game_objects.keys()
+            .filter(|id| self.check_if_visible_to(team, **id))
+            .cloned()
+            .collect();
+
+        ids.sort();
+        return ids;
+    }
+
+    // Ids of objects containing the point, in ascending order.
+    pub fn get_objects_at(&self, point: &Coordinates) -> Vec<u32> {
+        return self.spatial_index.get_candidates_at(point)
+            .into_iter()
+            .filter(|id| check_if_object_area_contains_coordinates(&self.game_objects[id], point))
+            .collect();
+    }
+
+    // Ids of objects overlapping the rectangle, in ascending order. Touching edges don't count.
+    pub fn get_objects_in_area(&self, position: &Coordinates, size: &Size) -> Vec<u32> {
+        return self.spatial_index.get_candidates_in_area(position, size)
+            .into_iter()
+            .filter(|id| {
+                let object = &self.game_objects[id];
+                algebra_basics::check_if_rectangles_overlap(position, size, &object.position, &object.size)
+            })
+            .collect();
+    }
+
+    // Distance is measured to the closest point of the object, objects containing the point are at 0.
+    pub fn get_nearest_object<F>(&self, point: &Coordinates, mut filter: F) -> Option<(u32, f64)>
+    where F: FnMut(u32, &GameObject) -> bool {
+        let last_ring = self.spatial_index.get_last_ring(point)?;
+        let cell_size = self.spatial_index.get_cell_size();
+        let mut nearest: Option<(u32, f64)> = None;
+
+        for ring in 0..=last_ring {
+            for id in self.spatial_index.get_candidates_in_ring(point, ring) {
+                let object = &self.game_objects[&id];
+                if !filter(id, object) {
+                    continue;
+                }
+
+                let distance = algebra_basics::get_distance_to_rectangle(point, &object.position, &object.size);
+                let is_closer = match nearest {
+                    Some((nearest_id, nearest_distance)) => distance < nearest_distance || (distance == nearest_distance && id < nearest_id),
+                    None => true
+                };
+                if is_closer {
+                    nearest = Some((id, distance));
+                }
+            }
+
+            // Cells further out are at least ring * cell_size away.
+            if let Some((_, distance)) = nearest {
+                if distance <= ring as f64 * cell_size {
+                    break;
+                }
+            }
+        }
+
+        return nearest;
+    }
+
+    // Objects crossed by the segment a-b with the distance from a to where it enters them, closest first.
+    pub fn cast_segment(&self, a: &Coordinates, b: &Coordinates) -> Vec<(u32, f64)> {
+        let (position, size) = algebra_basics::get_rectangle_from_corners(a, b);
+
+        let mut hits: Vec<(u32, f64)> = self.spatial_index.get_candidates_in_area(&position, &size)
+            .into_iter()
+            .filter_map(|id| {
+                let object = &self.game_objects[&id];
+                algebra_basics::get_segment_entry_distance(a, b, &object.position, &object.size)
+                    .map(|distance| (id, distance))
+            })
+            .collect();
+
+        hits.sort_by(|first, second| first.1.partial_cmp(&second.1).unwrap().then(first.0.cmp(&second.0)));
+        return hits;
+    }
 }
 
 pub fn check_if_object_area_contains_coordinates(object: &GameObject, coordinates: &Coordinates) -> bool {
@@ -76,13 +398,15 @@ pub fn check_if_object_area_contains_coordinates(object: &GameObject, coordinate
 mod tests {
     use super::*;
     use crate::testing::setup;
+    use crate::game_data::game_object::GameObjectType;
+    use crate::game_data::components::{Inventory, Team};
 
     #[test]
     fn gameboard_add_object_next_id_changes() {
         let mut gameboard = Gameboard::new();
 
         let id_pre_add = gameboard.next_id;
-        gameboard.add_object(setup::setup_selectable_object());
+        gameboard.add_object(setup::setup_selectable_object()).unwrap();
         let id_after_add = gameboard.next_id;
 
         assert_ne!(id_pre_add, id_after_add);
@@ -91,9 +415,9 @@ mod tests {
     #[test]
     fn gameboard_remove_object_correct_id_object_is_removed() {
         let mut gameboard = setup::setup_gameboard_with_selectable_object();
-        gameboard.remove_object(0);
+        gameboard.remove_object(0).unwrap();
 
-        let get_result = gameboard.game_objects.get(&0);
+        let get_result = gameboard.get_object_by_id(0);
 
         assert_eq!(get_result.is_none(), true);
     }
@@ -120,13 +444,225 @@ mod tests {
         let new_position = Coordinates::new(50.0, 50.0);
         let operation = GameboardObjectOperation::Move(new_position);
 
-        gameboard.execute_operation(0, operation);
-        let object = gameboard.game_objects.get(&0).unwrap();
+        gameboard.execute_operation(0, operation).unwrap();
+        let object = gameboard.get_object_by_id(0).unwrap();
         let expected_position = Coordinates::new(50.0, 50.0);
 
         assert_eq!(object.position, expected_position);
     }
 
+    #[test]
+    fn gameboard_add_object_outside_bounds_returns_error() {
+        let mut gameboard = Gameboard::new();
+        let object = GameObject::new(GameObjectType::Static, Coordinates::new(480.0, 0.0), Size::new(50.0, 50.0));
+
+        let result = gameboard.add_object(object);
+
+        assert_eq!(result, Err(GameboardError::OutOfBounds(Coordinates::new(480.0, 0.0))));
+        assert_eq!(gameboard.get_all_objects().is_empty(), true);
+    }
+
+    #[test]
+    fn gameboard_execute_operation_move_outside_bounds_returns_error_and_object_stays() {
+        let mut gameboard = setup::setup_gameboard_with_selectable_object();
+
+        let result = gameboard.execute_operation(0, GameboardObjectOperation::Move(Coordinates::new(-1.0, 0.0)));
+
+        assert_eq!(result, Err(GameboardError::OutOfBounds(Coordinates::new(-1.0, 0.0))));
+        assert_eq!(gameboard.get_object_by_id(0).unwrap().position, Coordinates::new(0.0, 0.0));
+    }
+
+    #[test]
+    fn gameboard_remove_object_unknown_id_returns_error() {
+        let mut gameboard = setup::setup_gameboard_with_selectable_object();
+
+        let result = gameboard.remove_object(5);
+
+        assert_eq!(result, Err(GameboardError::UnknownId(5)));
+    }
+
+    #[test]
+    fn gameboard_execute_operation_unknown_id_returns_error() {
+        let mut gameboard = setup::setup_gameboard_with_selectable_object();
+
+        let result = gameboard.execute_operation(5, GameboardObjectOperation::Move(Coordinates::new(10.0, 10.0)));
+
+        assert_eq!(result, Err(GameboardError::UnknownId(5)));
+    }
+
+    #[test]
+    fn gameboard_execute_operation_move_static_object_returns_error() {
+        let mut gameboard = Gameboard::new();
+        gameboard.add_object(GameObject::new(GameObjectType::Static, Coordinates::new(0.0, 0.0), Size::new(50.0, 50.0))).unwrap();
+
+        let result = gameboard.execute_operation(0, GameboardObjectOperation::Move(Coordinates::new(10.0, 10.0)));
+
+        assert_eq!(result, Err(GameboardError::ImmovableType(GameObjectType::Static)));
+    }
+
+    #[test]
+    fn gameboard_execute_operation_move_onto_obstacle_returns_error_and_object_stays() {
+        let mut gameboard = setup::setup_gameboard_with_selectable_object();
+        gameboard.add_object(GameObject::new(GameObjectType::Static, Coordinates::new(100.0, 0.0), Size::new(50.0, 50.0))).unwrap();
+
+        let result = gameboard.execute_operation(0, GameboardObjectOperation::Move(Coordinates::new(70.0, 0.0)));
+
+        assert_eq!(result, Err(GameboardError::Overlapping(1)));
+        assert_eq!(gameboard.get_object_by_id(0).unwrap().position, Coordinates::new(0.0, 0.0));
+    }
+
+    #[test]
+    fn gameboard_execute_operation_close_door_with_unit_inside_returns_error() {
+        let mut gameboard = Gameboard::new();
+        let door = GameObject::new(GameObjectType::Interactable, Coordinates::new(100.0, 0.0), Size::new(50.0, 50.0))
+            .with_interaction(Interaction::Door{open: true});
+        gameboard.add_object(door).unwrap();
+        gameboard.add_object(setup::setup_selectable_object_at(Coordinates::new(110.0, 0.0))).unwrap();
+
+        let result = gameboard.execute_operation(0, GameboardObjectOperation::SetInteraction(Interaction::Door{open: false}));
+
+        assert_eq!(result, Err(GameboardError::Overlapping(1)));
+        assert_eq!(gameboard.get_object_by_id(0).unwrap().is_passable(), true);
+    }
+
+    #[test]
+    fn gameboard_add_object_on_top_of_obstacle_returns_error() {
+        let mut gameboard = Gameboard::new();
+        gameboard.add_object(GameObject::new(GameObjectType::Static, Coordinates::new(100.0, 0.0), Size::new(50.0, 50.0))).unwrap();
+
+        let result = gameboard.add_object(setup::setup_selectable_object_at(Coordinates::new(120.0, 20.0)));
+
+        assert_eq!(result, Err(GameboardError::Overlapping(0)));
+    }
+
+    #[test]
+    fn gameboard_add_object_on_top_of_unit_object_is_added() {
+        let mut gameboard = setup::setup_gameboard_with_selectable_object();
+
+        let result = gameboard.add_object(setup::setup_selectable_object_at(Coordinates::new(20.0, 20.0)));
+
+        assert_eq!(result, Ok(1));
+    }
+
+    #[test]
+    fn gameboard_get_objects_with_components_only_objects_with_every_component_returned() {
+        let mut gameboard = setup::setup_gameboard_with_selectable_object();
+        let mut object = setup::setup_selectable_object_at(Coordinates::new(100.0, 0.0));
+        object.components.insert(Inventory::default());
+        gameboard.add_object(object).unwrap();
+
+        let result = gameboard.get_objects_with_components(&[ComponentKind::Inventory]);
+
+        assert_eq!(result, vec![1]);
+        assert_eq!(gameboard.get_component::<Inventory>(0).is_none(), true);
+    }
+
+    #[test]
+    fn gameboard_get_friendly_and_hostile_objects_objects_are_split_by_team() {
+        let mut gameboard = setup::setup_gameboard_with_selectable_object();
+        let mut enemy = setup::setup_selectable_object_at(Coordinates::new(100.0, 0.0));
+        enemy.components.insert(Team{id: 1});
+        gameboard.add_object(enemy).unwrap();
+        gameboard.add_object(GameObject::new(GameObjectType::Static, Coordinates::new(200.0, 0.0), Size::new(50.0, 50.0))).unwrap();
+
+        assert_eq!(gameboard.get_friendly_objects(0), vec![0]);
+        assert_eq!(gameboard.get_hostile_objects(0), vec![1]);
+        assert_eq!(gameboard.check_if_hostile(0, 1), true);
+        assert_eq!(gameboard.check_if_hostile(0, 2), false);
+    }
+
+    #[test]
+    fn gameboard_clamp_position_position_outside_bounds_object_is_moved_onto_board() {
+        let gameboard = Gameboard::with_bounds(Size::new(200.0, 100.0));
+
+        let result = gameboard.clamp_position(&Coordinates::new(300.0, -20.0), &Size::new(50.0, 50.0));
+
+        assert_eq!(result, Coordinates::new(150.0, 0.0));
+    }
+
+    fn setup_gameboard_with_row_of_objects() -> Gameboard {
+        let mut gameboard = Gameboard::new();
+        for i in 0..3 {
+            let position = Coordinates::new(100.0 * i as f64, 0.0);
+            gameboard.add_object(GameObject::new(GameObjectType::Selectable, position, Size::new(50.0, 50.0))).unwrap();
+        }
+
+        return gameboard;
+    }
+
+    #[test]
+    fn gameboard_get_objects_at_point_inside_object_returns_its_id() {
+        let gameboard = setup_gameboard_with_row_of_objects();
+
+        assert_eq!(gameboard.get_objects_at(&Coordinates::new(125.0, 25.0)), vec![1]);
+        assert_eq!(gameboard.get_objects_at(&Coordinates::new(75.0, 25.0)).is_empty(), true);
+    }
+
+    #[test]
+    fn gameboard_get_objects_in_area_overlapping_objects_are_returned() {
+        let gameboard = setup_gameboard_with_row_of_objects();
+
+        let result = gameboard.get_objects_in_area(&Coordinates::new(40.0, 10.0), &Size::new(80.0, 10.0));
+
+        assert_eq!(result, vec![0, 1]);
+    }
+
+    #[test]
+    fn gameboard_execute_operation_move_object_is_found_at_new_position() {
+        let mut gameboard = setup_gameboard_with_row_of_objects();
+
+        gameboard.execute_operation(0, GameboardObjectOperation::Move(Coordinates::new(400.0, 400.0))).unwrap();
+
+        assert_eq!(gameboard.get_objects_at(&Coordinates::new(25.0, 25.0)).is_empty(), true);
+        assert_eq!(gameboard.get_objects_at(&Coordinates::new(425.0, 425.0)), vec![0]);
+    }
+
+    #[test]
+    fn gameboard_remove_object_object_is_not_found_by_queries() {
+        let mut gameboard = setup_gameboard_with_row_of_objects();
+
+        gameboard.remove_object(1).unwrap();
+
+        assert_eq!(gameboard.get_objects_at(&Coordinates::new(125.0, 25.0)).is_empty(), true);
+    }
+
+    #[test]
+    fn gameboard_get_nearest_object_far_point_returns_closest_object_and_distance() {
+        let gameboard = setup_gameboard_with_row_of_objects();
+
+        let result = gameboard.get_nearest_object(&Coordinates::new(500.0, 25.0), |_, _| true);
+
+        assert_eq!(result, Some((2, 250.0)));
+    }
+
+    #[test]
+    fn gameboard_get_nearest_object_filter_provided_skips_rejected_objects() {
+        let gameboard = setup_gameboard_with_row_of_objects();
+
+        let result = gameboard.get_nearest_object(&Coordinates::new(25.0, 25.0), |id, _| id != 0);
+
+        assert_eq!(result, Some((1, 75.0)));
+    }
+
+    #[test]
+    fn gameboard_cast_segment_objects_are_sorted_by_entry_distance() {
+        let gameboard = setup_gameboard_with_row_of_objects();
+
+        let result = gameboard.cast_segment(&Coordinates::new(270.0, 25.0), &Coordinates::new(60.0, 25.0));
+
+        assert_eq!(result, vec![(2, 20.0), (1, 120.0)]);
+    }
+
+    #[test]
+    fn gameboard_deserialize_spatial_index_is_rebuilt() {
+        let gameboard = setup_gameboard_with_row_of_objects();
+        let text = serde_json::to_string(&gameboard).unwrap();
+
+        let restored: Gameboard = serde_json::from_str(&text).unwrap();
+
+        assert_eq!(restored.get_objects_at(&Coordinates::new(225.0, 25.0)), vec![2]);
+    }
+
     #[test]
     fn check_if_object_area_contains_coordinates_correct_coordinates_returns_true() {
         let coordinates = Coordinates::new(25.0, 25.0);
@@ -1,12 +1,26 @@
 pub mod gameboard;
+pub mod spatial_index;
+pub mod scenario;
+pub mod components;
+pub mod visibility;
+pub mod conditions;
 
 pub mod game_object {
+    use serde::{Serialize, Deserialize};
+
     use crate::algebra_basics::{Coordinates, Size};
+    use crate::game_data::components::{Components, Interaction, Speed};
+
+    // Units per second.
+    pub const DEFAULT_SPEED: f64 = 60.0;
     
+    #[derive(Clone, Serialize, Deserialize)]
     pub struct GameObject {
         pub object_type: GameObjectType,
         pub position: Coordinates,
-        pub size: Size
+        pub size: Size,
+        #[serde(default)]
+        pub components: Components
     }
     
     impl GameObject {
@@ -16,15 +30,49 @@ pub mod game_object {
                 GameObject {
                     object_type,
                     position,
-                    size
+                    size,
+                    components: Components::default()
                 }
         }
+
+        pub fn with_interaction(mut self, interaction: Interaction) -> GameObject {
+            self.components.insert(interaction);
+            self
+        }
+
+        pub fn get_speed(&self) -> f64 {
+            self.components.get::<Speed>().map_or(DEFAULT_SPEED, |speed| speed.value)
+        }
+
+        // Open doors can be walked through.
+        pub fn is_passable(&self) -> bool {
+            self.components.get::<Interaction>() == Some(&Interaction::Door{open: true})
+        }
+
+        pub fn is_obstacle(&self) -> bool {
+            self.object_type.is_obstacle() && !self.is_passable()
+        }
+
+        // Objects which can't be seen through, units never block the view.
+        pub fn is_opaque(&self) -> bool {
+            self.is_obstacle()
+        }
     }
-    
-    #[derive(Debug, PartialEq, Eq, Hash)]
+
+    #[derive(Debug, PartialEq, Eq, Hash, Clone, Serialize, Deserialize)]
     pub enum GameObjectType {
         Static,
         Interactable,
         Selectable
     }
+
+    impl GameObjectType {
+        // Units can't walk through walls or interactables, but can pass by each other.
+        pub fn is_obstacle(&self) -> bool {
+            match self {
+                GameObjectType::Static | GameObjectType::Interactable => true,
+                GameObjectType::Selectable => false
+            }
+        }
+    }
 }
\ No newline at end of file
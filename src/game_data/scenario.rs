@@ -0,0 +1,500 @@
+use std::collections::{BTreeMap, BTreeSet, HashMap};
+use std::fmt;
+use std::fs;
+use std::io;
+
+use serde::Deserialize;
+
+use crate::algebra_basics::{Coordinates, Size};
+use crate::game_data::gameboard::Gameboard;
+use crate::game_data::game_object::{GameObject, GameObjectType};
+use crate::game_data::components::{Attributes, ComponentData, Interaction, Speed};
+use crate::game_data::conditions::{Area, TeamCondition, VictoryCondition};
+
+// Looked up next to the manifest, so it is found from any working directory. Another file can be
+// passed as the first command line argument.
+pub const DEFAULT_SCENARIO_PATH: &str = concat!(env!("CARGO_MANIFEST_DIR"), "/scenarios/default.json");
+
+#[derive(Deserialize)]
+#[serde(deny_unknown_fields)]
+struct ScenarioFile {
+    board: BoardDefinition,
+    #[serde(default)]
+    spawn_points: Vec<SpawnPointDefinition>,
+    objects: Vec<ObjectDefinition>,
+    #[serde(default)]
+    initial_selection: Vec<String>,
+    #[serde(default)]
+    teams: Vec<TeamDefinition>,
+    #[serde(default)]
+    local_team: u32,
+    #[serde(default)]
+    mode: ModeDefinition,
+    #[serde(default)]
+    ai_seed: u64,
+    #[serde(default)]
+    victory: Vec<VictoryDefinition>
+}
+
+#[derive(Deserialize, Default, PartialEq, Debug)]
+enum ModeDefinition {
+    #[default]
+    RealTime,
+    TurnBased
+}
+
+#[derive(Deserialize)]
+#[serde(deny_unknown_fields)]
+struct TeamDefinition {
+    id: u32,
+    color: [f32; 4]
+}
+
+#[derive(Deserialize)]
+#[serde(deny_unknown_fields)]
+struct BoardDefinition {
+    width: f64,
+    height: f64
+}
+
+#[derive(Deserialize)]
+#[serde(deny_unknown_fields)]
+struct SpawnPointDefinition {
+    name: String,
+    position: Coordinates
+}
+
+// An object is placed either at an explicit position or at a named spawn point.
+#[derive(Deserialize)]
+#[serde(deny_unknown_fields)]
+struct ObjectDefinition {
+    name: Option<String>,
+    #[serde(rename = "type")]
+    object_type: GameObjectType,
+    position: Option<Coordinates>,
+    spawn: Option<String>,
+    size: Size,
+    speed: Option<f64>, // same as a Speed component
+    #[serde(default)]
+    attributes: BTreeMap<String, String>,
+    interaction: Option<InteractionDefinition>,
+    #[serde(default)]
+    components: Vec<ComponentData>
+}
+
+// Same as Interaction, except that switches name the objects they toggle.
+#[derive(Deserialize)]
+#[serde(tag = "kind", deny_unknown_fields)]
+enum InteractionDefinition {
+    Door{#[serde(default)] open: bool},
+    Pickup{item: String},
+    CapturePoint,
+    Switch{targets: Vec<String>}
+}
+
+#[derive(Deserialize)]
+#[serde(deny_unknown_fields)]
+struct VictoryDefinition {
+    team: u32,
+    condition: ConditionDefinition
+}
+
+// Same as VictoryCondition, except that the unit reaching the exit is named.
+#[derive(Deserialize)]
+#[serde(deny_unknown_fields)]
+enum ConditionDefinition {
+    EliminateAllEnemies,
+    HoldArea{area: Area, seconds: f64},
+    SurviveTurns{turns: u32},
+    ReachExit{unit: String, area: Area}
+}
+
+pub struct Scenario {
+    pub gameboard: Gameboard,
+    pub spawn_points: HashMap<String, Coordinates>,
+    pub initial_selection: BTreeSet<u32>,
+    pub local_team: u32,
+    pub team_colors: HashMap<u32, [f32; 4]>,
+    pub turn_based: bool, // teams alternate turns instead of acting all at once
+    pub ai_seed: u64,
+    pub victory_conditions: Vec<TeamCondition> // in the order they're checked
+}
+
+#[derive(Debug)]
+pub enum ScenarioError {
+    Io(io::Error),
+    Syntax{line: usize, column: usize, message: String},
+    Invalid{field: String, object: Option<String>, message: String} // object is the name of the object the field belongs to
+}
+
+impl fmt::Display for ScenarioError {
+    fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
+        match &self {
+            ScenarioError::Io(error) => write!(f, "Could not read scenario: {}", error),
+            ScenarioError::Syntax{line, column, message} => write!(f, "Line {}, column {}: {}", line, column, message),
+            ScenarioError::Invalid{field, object: Some(name), message} => write!(f, "Field {} of object {}: {}", field, name, message),
+            ScenarioError::Invalid{field, object: None, message} => write!(f, "Field {}: {}", field, message),
+        }
+    }
+}
+
+impl From<io::Error> for ScenarioError {
+    fn from(error: io::Error) -> ScenarioError {
+        ScenarioError::Io(error)
+    }
+}
+
+impl From<serde_json::Error> for ScenarioError {
+    fn from(error: serde_json::Error) -> ScenarioError {
+        let (line, column) = (error.line(), error.column());
+        // serde_json puts the location at the end of the message, it's reported separately here.
+        let message = error.to_string()
+            .trim_end_matches(&format!(" at line {} column {}", line, column))
+            .to_string();
+
+        ScenarioError::Syntax{line, column, message}
+    }
+}
+
+fn invalid(field: String, message: &str) -> ScenarioError {
+    ScenarioError::Invalid{field, object: None, message: message.to_string()}
+}
+
+fn invalid_object(field: String, name: &Option<String>, message: &str) -> ScenarioError {
+    ScenarioError::Invalid{field, object: name.clone(), message: message.to_string()}
+}
+
+pub fn load_from_file(path: &str) -> Result<Scenario, ScenarioError> {
+    let text = fs::read_to_string(path)?;
+    return parse(&text);
+}
+
+pub fn parse(text: &str) -> Result<Scenario, ScenarioError> {
+    let file: ScenarioFile = serde_json::from_str(text)?;
+
+    if file.board.width <= 0.0 || file.board.height <= 0.0 {
+        return Err(invalid("board".to_string(), "width and height have to be positive"));
+    }
+
+    let mut spawn_points = HashMap::new();
+    for (i, spawn_point) in file.spawn_points.into_iter().enumerate() {
+        if spawn_points.insert(spawn_point.name, spawn_point.position).is_some() {
+            return Err(invalid(format!("spawn_points[{}].name", i), "spawn point name is already used"));
+        }
+    }
+
+    let mut gameboard = Gameboard::with_bounds(Size::new(file.board.width, file.board.height));
+    let mut ids_by_name = HashMap::new();
+    let mut switches = Vec::new();
+
+    for (i, definition) in file.objects.into_iter().enumerate() {
+        let name = definition.name.clone();
+        let position = match (definition.position, &definition.spawn) {
+            (Some(position), None) => position,
+            (None, Some(spawn)) => match spawn_points.get(spawn) {
+                Some(position) => position.clone(),
+                None => return Err(invalid_object(format!("objects[{}].spawn", i), &name, "unknown spawn point"))
+            },
+            _ => return Err(invalid_object(format!("objects[{}]", i), &name, "exactly one of position and spawn has to be set"))
+        };
+
+        if definition.size.width <= 0.0 || definition.size.height <= 0.0 {
+            return Err(invalid_object(format!("objects[{}].size", i), &name, "width and height have to be positive"));
+        }
+
+        let mut object = GameObject::new(definition.object_type, position, definition.size);
+        object.components = definition.components.into();
+        if !definition.attributes.is_empty() {
+            object.components.insert(Attributes{values: definition.attributes});
+        }
+        if let Some(value) = definition.speed {
+            object.components.insert(Speed{value});
+        }
+        if object.get_speed() < 0.0 {
+            return Err(invalid_object(format!("objects[{}].speed", i), &name, "speed can't be negative"));
+        }
+        let interaction = match definition.interaction {
+            Some(_) if object.object_type != GameObjectType::Interactable => {
+                return Err(invalid_object(format!("objects[{}].interaction", i), &name, "only Interactable objects can have an interaction"));
+            },
+            Some(InteractionDefinition::Door{open}) => Some(Interaction::Door{open}),
+            Some(InteractionDefinition::Pickup{item}) => Some(Interaction::Pickup{item}),
+            Some(InteractionDefinition::CapturePoint) => Some(Interaction::CapturePoint{captured_by: None}),
+            Some(InteractionDefinition::Switch{targets}) => {
+                switches.push((i, name.clone(), targets));
+                Some(Interaction::Switch{targets: Vec::new()})
+            },
+            None => None
+        };
+        if let Some(interaction) = interaction {
+            object.components.insert(interaction);
+        }
+        let id = match gameboard.add_object(object) {
+            Ok(id) => id,
+            Err(error) => return Err(ScenarioError::Invalid{field: format!("objects[{}]", i), object: name, message: error.to_string()})
+        };
+
+        if let Some(object_name) = definition.name {
+            if ids_by_name.insert(object_name, id).is_some() {
+                return Err(invalid_object(format!("objects[{}].name", i), &name, "object name is already used"));
+            }
+        }
+    }
+
+    // Switch targets can be declared after the switch, so they're resolved once every object is placed.
+    // Objects are added in declaration order, so the i-th definition got id i.
+    for (i, switch_name, names) in switches {
+        let mut targets = Vec::new();
+        for (j, name) in names.iter().enumerate() {
+            match ids_by_name.get(name) {
+                Some(id) => targets.push(*id),
+                None => return Err(invalid_object(format!("objects[{}].interaction.targets[{}]", i, j), &switch_name, "unknown object name"))
+            }
+        }
+
+        if let Some(switch) = gameboard.get_object_by_id_mut(i as u32) {
+            switch.components.insert(Interaction::Switch{targets});
+        }
+    }
+
+    let mut initial_selection = BTreeSet::new();
+    for (i, name) in file.initial_selection.iter().enumerate() {
+        let id = match ids_by_name.get(name) {
+            Some(id) => *id,
+            None => return Err(invalid(format!("initial_selection[{}]", i), "unknown object name"))
+        };
+
+        if gameboard.get_object_by_id(id).map(|object| &object.object_type) != Some(&GameObjectType::Selectable) {
+            return Err(invalid(format!("initial_selection[{}]", i), "only Selectable objects can be selected"));
+        }
+
+        if gameboard.get_team(id) != Some(file.local_team) {
+            return Err(invalid(format!("initial_selection[{}]", i), "only units of the local team can be selected"));
+        }
+
+        initial_selection.insert(id);
+    }
+
+    let mut team_colors = HashMap::new();
+    for (i, team) in file.teams.into_iter().enumerate() {
+        if team_colors.insert(team.id, team.color).is_some() {
+            return Err(invalid(format!("teams[{}].id", i), "team id is already used"));
+        }
+    }
+
+    let turn_based = file.mode == ModeDefinition::TurnBased;
+    let mut victory_conditions = Vec::new();
+    for (i, definition) in file.victory.into_iter().enumerate() {
+        let condition = match definition.condition {
+            ConditionDefinition::EliminateAllEnemies => VictoryCondition::EliminateAllEnemies,
+            ConditionDefinition::HoldArea{area, seconds} => {
+                if seconds < 0.0 {
+                    return Err(invalid(format!("victory[{}].condition.seconds", i), "seconds can't be negative"));
+                }
+                VictoryCondition::HoldArea{area, seconds}
+            },
+            ConditionDefinition::SurviveTurns{turns} => {
+                if !turn_based {
+                    return Err(invalid(format!("victory[{}].condition", i), "turns can only be survived in the TurnBased mode"));
+                }
+                VictoryCondition::SurviveTurns{turns}
+            },
+            ConditionDefinition::ReachExit{unit, area} => {
+                let unit_id = match ids_by_name.get(&unit) {
+                    Some(id) => *id,
+                    None => return Err(invalid(format!("victory[{}].condition.unit", i), "unknown object name"))
+                };
+
+                if gameboard.get_team(unit_id) != Some(definition.team) {
+                    return Err(invalid(format!("victory[{}].condition.unit", i), "only a unit of the winning team can reach the exit"));
+                }
+                VictoryCondition::ReachExit{unit_id, area}
+            }
+        };
+
+        victory_conditions.push(TeamCondition{team: definition.team, condition});
+    }
+
+    Ok(Scenario {
+        gameboard,
+        spawn_points,
+        initial_selection,
+        local_team: file.local_team,
+        team_colors,
+        turn_based,
+        ai_seed: file.ai_seed,
+        victory_conditions
+    })
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use crate::game_data::components::Inventory;
+
+    const VALID_SCENARIO: &str = r#"{
+        "board": {"width": 512.0, "height": 256.0},
+        "spawn_points": [{"name": "west", "position": {"x": 10.0, "y": 20.0}}],
+        "objects": [
+            {"name": "alpha", "type": "Selectable", "spawn": "west", "size": {"width": 50.0, "height": 50.0}, "speed": 30.0, "components": [{"Team": {"id": 1}}]},
+            {"type": "Static", "position": {"x": 200.0, "y": 0.0}, "size": {"width": 20.0, "height": 200.0}, "attributes": {"material": "stone"},
+                "components": [{"Inventory": {"items": ["torch"]}}]}
+        ],
+        "initial_selection": ["alpha"],
+        "teams": [{"id": 1, "color": [0.0, 0.0, 0.8, 1.0]}, {"id": 2, "color": [0.8, 0.0, 0.0, 1.0]}],
+        "local_team": 1
+    }"#;
+
+    fn assert_invalid_field(text: &str, expected_field: &str) {
+        match parse(text) {
+            Err(ScenarioError::Invalid{field, ..}) => assert_eq!(field, expected_field),
+            Err(error) => panic!("unexpected error: {}", error),
+            Ok(_) => panic!("expected an error")
+        }
+    }
+
+    #[test]
+    fn parse_valid_scenario_gameboard_is_built() {
+        let scenario = parse(VALID_SCENARIO).unwrap();
+        let alpha = scenario.gameboard.get_object_by_id(0).unwrap();
+        let wall = scenario.gameboard.get_object_by_id(1).unwrap();
+
+        assert_eq!(alpha.position, Coordinates::new(10.0, 20.0));
+        assert_eq!(alpha.get_speed(), 30.0);
+        assert_eq!(wall.object_type, GameObjectType::Static);
+        assert_eq!(wall.components.get::<Attributes>().and_then(|attributes| attributes.values.get("material")), Some(&"stone".to_string()));
+        assert_eq!(wall.components.get::<Inventory>().map(|inventory| inventory.items.clone()), Some(vec!["torch".to_string()]));
+        assert_eq!(scenario.initial_selection.contains(&0), true);
+        assert_eq!(scenario.local_team, 1);
+        assert_eq!(scenario.team_colors.get(&2), Some(&[0.8, 0.0, 0.0, 1.0]));
+        let bounds = scenario.gameboard.get_bounds();
+        assert_eq!((bounds.width, bounds.height), (512.0, 256.0));
+    }
+
+    #[test]
+    fn parse_missing_field_error_with_line_is_returned() {
+        let text = "{\n\"board\": {\"width\": 512.0, \"height\": 256.0},\n\"objects\": [\n{\"type\": \"Static\", \"position\": {\"x\": 0.0, \"y\": 0.0}}\n]\n}";
+
+        match parse(text) {
+            Err(ScenarioError::Syntax{line, message, ..}) => {
+                assert_eq!(line, 4);
+                assert_eq!(message, "missing field `size`");
+            },
+            _ => panic!("expected a syntax error")
+        }
+    }
+
+    #[test]
+    fn parse_unknown_object_type_error_with_line_is_returned() {
+        let text = VALID_SCENARIO.replace("\"Static\"", "\"Wall\"");
+
+        match parse(&text) {
+            Err(ScenarioError::Syntax{line, ..}) => assert_eq!(line, 6),
+            _ => panic!("expected a syntax error")
+        }
+    }
+
+    #[test]
+    fn parse_unknown_spawn_point_error_with_field_is_returned() {
+        let text = VALID_SCENARIO.replace("\"spawn\": \"west\"", "\"spawn\": \"east\"");
+        assert_invalid_field(&text, "objects[0].spawn");
+    }
+
+    #[test]
+    fn parse_unknown_selected_object_error_with_field_is_returned() {
+        let text = VALID_SCENARIO.replace("[\"alpha\"]", "[\"beta\"]");
+        assert_invalid_field(&text, "initial_selection[0]");
+    }
+
+    #[test]
+    fn parse_static_object_selected_error_with_field_is_returned() {
+        let text = VALID_SCENARIO.replace("\"type\": \"Static\"", "\"name\": \"wall\", \"type\": \"Static\"")
+            .replace("[\"alpha\"]", "[\"alpha\", \"wall\"]");
+        assert_invalid_field(&text, "initial_selection[1]");
+    }
+
+    #[test]
+    fn parse_unit_of_other_team_selected_error_with_field_is_returned() {
+        let text = VALID_SCENARIO.replace("\"local_team\": 1", "\"local_team\": 2");
+        assert_invalid_field(&text, "initial_selection[0]");
+    }
+
+    #[test]
+    fn parse_switch_targets_names_are_resolved_to_ids() {
+        let text = VALID_SCENARIO.replace("{\"type\": \"Static\"", r#"{"type": "Interactable", "position": {"x": 300.0, "y": 0.0}, "size": {"width": 10.0, "height": 10.0}, "interaction": {"kind": "Switch", "targets": ["door"]}},
+            {"name": "door", "type": "Interactable", "position": {"x": 400.0, "y": 0.0}, "size": {"width": 10.0, "height": 40.0}, "interaction": {"kind": "Door"}},
+            {"type": "Static""#);
+
+        let scenario = parse(&text).unwrap();
+
+        assert_eq!(scenario.gameboard.get_component::<Interaction>(1), Some(&Interaction::Switch{targets: vec![2]}));
+        assert_eq!(scenario.gameboard.get_component::<Interaction>(2), Some(&Interaction::Door{open: false}));
+    }
+
+    #[test]
+    fn parse_interaction_on_static_object_error_with_field_is_returned() {
+        let text = VALID_SCENARIO.replace("\"attributes\"", "\"interaction\": {\"kind\": \"CapturePoint\"}, \"attributes\"");
+        assert_invalid_field(&text, "objects[1].interaction");
+    }
+
+    #[test]
+    fn parse_position_and_spawn_both_set_error_with_field_is_returned() {
+        let text = VALID_SCENARIO.replace("\"spawn\": \"west\"", "\"spawn\": \"west\", \"position\": {\"x\": 0.0, \"y\": 0.0}");
+        assert_invalid_field(&text, "objects[0]");
+    }
+
+    #[test]
+    fn parse_object_outside_board_error_with_field_is_returned() {
+        let text = VALID_SCENARIO.replace("\"x\": 200.0, \"y\": 0.0", "\"x\": 500.0, \"y\": 0.0");
+        assert_invalid_field(&text, "objects[1]");
+    }
+
+    #[test]
+    fn parse_named_object_invalid_error_names_the_object() {
+        let text = VALID_SCENARIO.replace("\"speed\": 30.0", "\"speed\": -30.0");
+
+        let error = parse(&text).err().unwrap();
+
+        assert_eq!(error.to_string(), "Field objects[0].speed of object alpha: speed can't be negative");
+    }
+
+    #[test]
+    fn parse_default_scenario_file_is_valid() {
+        let scenario = load_from_file(DEFAULT_SCENARIO_PATH).unwrap();
+
+        assert_eq!(scenario.gameboard.get_all_objects().len(), 3);
+    }
+
+    #[test]
+    fn parse_turn_based_mode_scenario_is_turn_based() {
+        let text = VALID_SCENARIO.replace("\"local_team\": 1", "\"local_team\": 1, \"mode\": \"TurnBased\"");
+
+        let scenario = parse(&text).unwrap();
+
+        assert_eq!(scenario.turn_based, true);
+    }
+
+    #[test]
+    fn parse_reach_exit_condition_unit_name_is_resolved() {
+        let text = VALID_SCENARIO.replace("\"local_team\": 1", r#""local_team": 1,
+            "victory": [{"team": 1, "condition": {"ReachExit": {"unit": "alpha", "area": {"position": {"x": 400.0, "y": 0.0}, "size": {"width": 50.0, "height": 50.0}}}}}]"#);
+
+        let scenario = parse(&text).unwrap();
+
+        let area = Area{position: Coordinates::new(400.0, 0.0), size: Size::new(50.0, 50.0)};
+        assert_eq!(scenario.victory_conditions, vec![TeamCondition{team: 1, condition: VictoryCondition::ReachExit{unit_id: 0, area}}]);
+    }
+
+    #[test]
+    fn parse_exit_reached_by_unknown_unit_error_with_field_is_returned() {
+        let text = VALID_SCENARIO.replace("\"local_team\": 1", r#""local_team": 1,
+            "victory": [{"team": 2, "condition": "EliminateAllEnemies"}, {"team": 1, "condition": {"ReachExit": {"unit": "beta", "area": {"position": {"x": 0.0, "y": 0.0}, "size": {"width": 5.0, "height": 5.0}}}}}]"#);
+        assert_invalid_field(&text, "victory[1].condition.unit");
+    }
+
+    #[test]
+    fn parse_survive_turns_in_real_time_error_with_field_is_returned() {
+        let text = VALID_SCENARIO.replace("\"local_team\": 1", r#""local_team": 1, "victory": [{"team": 1, "condition": {"SurviveTurns": {"turns": 5}}}]"#);
+        assert_invalid_field(&text, "victory[0].condition");
+    }
+}
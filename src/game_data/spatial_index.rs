@@ -0,0 +1,172 @@
+use std::collections::{BTreeSet, HashMap};
+
+use crate::algebra_basics::{Coordinates, Size};
+
+pub const DEFAULT_CELL_SIZE: f64 = 64.0;
+
+type Cell = (i64, i64);
+
+// Uniform grid, every object is registered in each cell its rectangle touches.
+// It only narrows down candidates, callers still run the exact geometric checks.
+#[derive(Clone)]
+pub struct SpatialIndex {
+    cell_size: f64,
+    cells: HashMap<Cell, Vec<u32>>,
+    // Grows with inserts and never shrinks, it only limits the nearest-neighbour search.
+    occupied_bounds: Option<(Cell, Cell)>
+}
+
+impl Default for SpatialIndex {
+    fn default() -> SpatialIndex {
+        SpatialIndex::new(DEFAULT_CELL_SIZE)
+    }
+}
+
+impl SpatialIndex {
+    pub fn new(cell_size: f64) -> SpatialIndex {
+        SpatialIndex {
+            cell_size,
+            cells: HashMap::new(),
+            occupied_bounds: None
+        }
+    }
+
+    pub fn get_cell_size(&self) -> f64 {
+        self.cell_size
+    }
+
+    pub fn insert(&mut self, id: u32, position: &Coordinates, size: &Size) {
+        let (min, max) = self.get_cell_range(position, size);
+
+        for x in min.0..=max.0 {
+            for y in min.1..=max.1 {
+                self.cells.entry((x, y)).or_default().push(id);
+            }
+        }
+
+        self.occupied_bounds = match self.occupied_bounds {
+            Some((bounds_min, bounds_max)) => Some((
+                (bounds_min.0.min(min.0), bounds_min.1.min(min.1)),
+                (bounds_max.0.max(max.0), bounds_max.1.max(max.1)))),
+            None => Some((min, max))
+        };
+    }
+
+    // position and size have to be the ones the object was inserted with.
+    pub fn remove(&mut self, id: u32, position: &Coordinates, size: &Size) {
+        let (min, max) = self.get_cell_range(position, size);
+
+        for x in min.0..=max.0 {
+            for y in min.1..=max.1 {
+                if let Some(ids) = self.cells.get_mut(&(x, y)) {
+                    ids.retain(|cell_id| *cell_id != id);
+                    if ids.is_empty() {
+                        self.cells.remove(&(x, y));
+                    }
+                }
+            }
+        }
+    }
+
+    pub fn get_candidates_at(&self, point: &Coordinates) -> BTreeSet<u32> {
+        return self.get_candidates_in_area(point, &Size::new(0.0, 0.0));
+    }
+
+    pub fn get_candidates_in_area(&self, position: &Coordinates, size: &Size) -> BTreeSet<u32> {
+        let (min, max) = self.get_cell_range(position, size);
+        let mut candidates = BTreeSet::new();
+
+        for x in min.0..=max.0 {
+            for y in min.1..=max.1 {
+                if let Some(ids) = self.cells.get(&(x, y)) {
+                    candidates.extend(ids.iter());
+                }
+            }
+        }
+
+        return candidates;
+    }
+
+    // Objects registered in the cells exactly `ring` cells away (Chebyshev distance) from the point's cell.
+    // Anything first found in ring n is at least (n - 1) * cell_size away from the point.
+    pub fn get_candidates_in_ring(&self, point: &Coordinates, ring: i64) -> BTreeSet<u32> {
+        let (center_x, center_y) = self.get_cell(point);
+        let mut candidates = BTreeSet::new();
+
+        for x in (center_x - ring)..=(center_x + ring) {
+            for y in (center_y - ring)..=(center_y + ring) {
+                if (x - center_x).abs() != ring && (y - center_y).abs() != ring {
+                    continue;
+                }
+                if let Some(ids) = self.cells.get(&(x, y)) {
+                    candidates.extend(ids.iter());
+                }
+            }
+        }
+
+        return candidates;
+    }
+
+    // Past this ring there are no registered cells around the point.
+    pub fn get_last_ring(&self, point: &Coordinates) -> Option<i64> {
+        let (min, max) = self.occupied_bounds?;
+        let (x, y) = self.get_cell(point);
+
+        let ring = (x - min.0).abs()
+            .max((x - max.0).abs())
+            .max((y - min.1).abs())
+            .max((y - max.1).abs());
+
+        return Some(ring);
+    }
+
+    fn get_cell(&self, point: &Coordinates) -> Cell {
+        ((point.x / self.cell_size).floor() as i64, (point.y / self.cell_size).floor() as i64)
+    }
+
+    fn get_cell_range(&self, position: &Coordinates, size: &Size) -> (Cell, Cell) {
+        let min = self.get_cell(position);
+        let max = self.get_cell(&Coordinates::new(position.x + size.width, position.y + size.height));
+        return (min, max);
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn spatial_index_insert_object_spanning_cells_is_found_in_each() {
+        let mut index = SpatialIndex::new(10.0);
+        index.insert(3, &Coordinates::new(5.0, 5.0), &Size::new(10.0, 10.0));
+
+        assert_eq!(index.get_candidates_at(&Coordinates::new(1.0, 1.0)).contains(&3), true);
+        assert_eq!(index.get_candidates_at(&Coordinates::new(14.0, 14.0)).contains(&3), true);
+        assert_eq!(index.get_candidates_at(&Coordinates::new(25.0, 25.0)).is_empty(), true);
+    }
+
+    #[test]
+    fn spatial_index_remove_object_is_no_longer_found() {
+        let mut index = SpatialIndex::new(10.0);
+        let position = Coordinates::new(5.0, 5.0);
+        let size = Size::new(10.0, 10.0);
+        index.insert(3, &position, &size);
+
+        index.remove(3, &position, &size);
+
+        assert_eq!(index.get_candidates_in_area(&position, &size).is_empty(), true);
+    }
+
+    #[test]
+    fn spatial_index_get_candidates_in_ring_only_cells_on_ring_are_returned() {
+        let mut index = SpatialIndex::new(10.0);
+        index.insert(0, &Coordinates::new(5.0, 5.0), &Size::new(1.0, 1.0));
+        index.insert(1, &Coordinates::new(15.0, 5.0), &Size::new(1.0, 1.0));
+        index.insert(2, &Coordinates::new(25.0, 25.0), &Size::new(1.0, 1.0));
+
+        let result = index.get_candidates_in_ring(&Coordinates::new(5.0, 5.0), 1);
+
+        assert_eq!(result.into_iter().collect::<Vec<u32>>(), vec![1]);
+        assert_eq!(index.get_last_ring(&Coordinates::new(5.0, 5.0)), Some(2));
+    }
+}
@@ -0,0 +1,101 @@
+use serde::{Serialize, Deserialize};
+
+use crate::algebra_basics::{Coordinates, Size};
+
+pub const VISIBILITY_CELL_SIZE: f64 = 16.0;
+
+#[derive(Debug, PartialEq, Eq, Clone, Copy, Serialize, Deserialize)]
+pub enum CellVisibility {
+    Hidden, // never seen
+    Explored, // seen before, but not right now
+    Visible
+}
+
+// What one team knows about the board, kept as a grid of square cells covering it.
+#[derive(Debug, PartialEq, Clone, Serialize, Deserialize)]
+pub struct VisibilityMap {
+    columns: usize,
+    rows: usize,
+    cells: Vec<CellVisibility>
+}
+
+impl VisibilityMap {
+    pub fn new(bounds: &Size) -> VisibilityMap {
+        let columns = (bounds.width / VISIBILITY_CELL_SIZE).ceil().max(1.0) as usize;
+        let rows = (bounds.height / VISIBILITY_CELL_SIZE).ceil().max(1.0) as usize;
+
+        VisibilityMap {
+            columns,
+            rows,
+            cells: vec![CellVisibility::Hidden; columns * rows]
+        }
+    }
+
+    pub fn get(&self, column: usize, row: usize) -> CellVisibility {
+        self.cells[row * self.columns + column]
+    }
+
+    pub fn reveal(&mut self, column: usize, row: usize) {
+        self.cells[row * self.columns + column] = CellVisibility::Visible;
+    }
+
+    // Turns what is seen right now into explored area, before the map is filled in again.
+    pub fn hide_visible(&mut self) {
+        for cell in self.cells.iter_mut().filter(|cell| **cell == CellVisibility::Visible) {
+            *cell = CellVisibility::Explored;
+        }
+    }
+
+    pub fn get_cell_center(column: usize, row: usize) -> Coordinates {
+        Coordinates::new((column as f64 + 0.5) * VISIBILITY_CELL_SIZE, (row as f64 + 0.5) * VISIBILITY_CELL_SIZE)
+    }
+
+    // Columns and rows of the cells the rectangle touches, limited to the map.
+    pub fn get_cell_range(&self, position: &Coordinates, size: &Size) -> ((usize, usize), (usize, usize)) {
+        let to_index = |value: f64, count: usize| ((value / VISIBILITY_CELL_SIZE).floor().max(0.0) as usize).min(count - 1);
+
+        let min = (to_index(position.x, self.columns), to_index(position.y, self.rows));
+        let max = (to_index(position.x + size.width, self.columns), to_index(position.y + size.height, self.rows));
+        (min, max)
+    }
+
+    // True when any part of the rectangle is seen right now.
+    pub fn check_if_area_is_visible(&self, position: &Coordinates, size: &Size) -> bool {
+        let (min, max) = self.get_cell_range(position, size);
+
+        (min.1..=max.1).any(|row| (min.0..=max.0).any(|column| self.get(column, row) == CellVisibility::Visible))
+    }
+
+    // Upper left corner of every cell together with its state, row by row.
+    pub fn iter(&self) -> impl Iterator<Item = (Coordinates, CellVisibility)> + '_ {
+        self.cells.iter().enumerate().map(move |(index, cell)| {
+            let position = Coordinates::new((index % self.columns) as f64 * VISIBILITY_CELL_SIZE, (index / self.columns) as f64 * VISIBILITY_CELL_SIZE);
+            (position, *cell)
+        })
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn hide_visible_revealed_cell_becomes_explored() {
+        let mut map = VisibilityMap::new(&Size::new(64.0, 64.0));
+        map.reveal(1, 2);
+
+        map.hide_visible();
+
+        assert_eq!(map.get(1, 2), CellVisibility::Explored);
+        assert_eq!(map.get(0, 0), CellVisibility::Hidden);
+    }
+
+    #[test]
+    fn check_if_area_is_visible_area_touching_revealed_cell_true() {
+        let mut map = VisibilityMap::new(&Size::new(64.0, 64.0));
+        map.reveal(2, 0);
+
+        assert_eq!(map.check_if_area_is_visible(&Coordinates::new(20.0, 0.0), &Size::new(20.0, 10.0)), true);
+        assert_eq!(map.check_if_area_is_visible(&Coordinates::new(0.0, 20.0), &Size::new(20.0, 10.0)), false);
+    }
+}
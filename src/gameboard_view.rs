@@ -1,25 +1,169 @@
-use graphics::types::Color;
 use graphics::{Context, Graphics};
 
 use crate::debug_utils::LineObject;
+use crate::game_controller::line_of_sight::LineOfSight;
+use crate::game_controller::turns::PathPreview;
+use crate::game_controller::movement_manager::MovementHandler;
+use crate::game_controller::order_queues::OrderQueue;
+use crate::game_controller::rules::GameResult;
+use std::collections::HashMap;
+
 use crate::game_data::game_object::GameObject;
+use crate::game_data::components::Team;
+use crate::game_data::conditions::{TeamCondition, VictoryCondition};
+use crate::game_data::visibility::{VisibilityMap, CellVisibility, VISIBILITY_CELL_SIZE};
+use crate::algebra_basics::Coordinates;
+use crate::algebra_basics;
+
+const NEUTRAL_COLOR: [f32; 4] = [0.0, 0.0, 0.2, 1.0];
+// Used for teams which have no color of their own.
+const TEAM_PALETTE: [[f32; 4]; 4] = [
+    [0.0, 0.0, 0.6, 1.0],
+    [0.6, 0.0, 0.0, 1.0],
+    [0.0, 0.5, 0.0, 1.0],
+    [0.6, 0.5, 0.0, 1.0]
+];
+
+fn get_object_color(object: &GameObject, team_colors: &HashMap<u32, [f32; 4]>) -> [f32; 4] {
+    match object.components.get::<Team>() {
+        Some(team) => *team_colors.get(&team.id).unwrap_or(&TEAM_PALETTE[team.id as usize % TEAM_PALETTE.len()]),
+        None => NEUTRAL_COLOR
+    }
+}
 
-pub fn render<G: Graphics>(objects: Vec<&GameObject>, c: &Context, g: &mut G){
+pub fn render<G: Graphics>(objects: Vec<&GameObject>, team_colors: &HashMap<u32, [f32; 4]>, c: &Context, g: &mut G){
     use graphics::{Rectangle};
 
     for object in objects {
+        let color = get_object_color(object, team_colors);
         let position = &object.position;
         let size = &object.size;
         let object_dimensions = [position.x, position.y, size.width, size.height];
 
-        Rectangle::new([0.0, 0.0, 0.2, 1.0])
-            .draw(object_dimensions, &c.draw_state, c.transform, g);
+        // Passable objects such as open doors are only outlined.
+        let rectangle = if object.is_passable() {
+            Rectangle::new_border(color, 1.0)
+        } else {
+            Rectangle::new(color)
+        };
+        rectangle.draw(object_dimensions, &c.draw_state, c.transform, g);
     }
 }
 
-pub fn render_debug<G: Graphics>(line_object: &LineObject, c: &Context, g: &mut G) {
+// Covers never seen cells completely and dims explored ones which aren't seen right now.
+pub fn render_fog<G: Graphics>(map: &VisibilityMap, c: &Context, g: &mut G) {
+    use graphics::Rectangle;
+
+    for (position, visibility) in map.iter() {
+        let color = match visibility {
+            CellVisibility::Hidden => [0.1, 0.1, 0.1, 1.0],
+            CellVisibility::Explored => [0.1, 0.1, 0.1, 0.4],
+            CellVisibility::Visible => continue
+        };
+        Rectangle::new(color)
+            .draw([position.x, position.y, VISIBILITY_CELL_SIZE, VISIBILITY_CELL_SIZE], &c.draw_state, c.transform, g);
+    }
+}
+
+// The whole path is drawn grey, the part the unit can afford this turn green on top of it.
+pub fn render_path_previews<G: Graphics>(previews: &[PathPreview], c: &Context, g: &mut G) {
+    for preview in previews {
+        render_path(&preview.full, [0.5, 0.5, 0.5, 1.0], c, g);
+        render_path(&preview.affordable, [0.0, 0.6, 0.0, 1.0], c, g);
+    }
+}
+
+fn render_path<G: Graphics>(movement: &MovementHandler, color: [f32; 4], c: &Context, g: &mut G) {
     use graphics::Line;
-    let line = [line_object.a.x, line_object.a.y, line_object.b.x, line_object.b.y];
+
+    let mut previous = movement.get_current_position();
+    for waypoint in movement.get_path() {
+        Line::new(color, 1.0)
+            .draw([previous.x, previous.y, waypoint.x, waypoint.y], &c.draw_state, c.transform, g);
+        previous = waypoint;
+    }
+}
+
+// Lines from each unit through its queued waypoints, a looping queue is closed back to its first waypoint.
+pub fn render_order_queues<G: Graphics>(queues: &[(Coordinates, &OrderQueue)], c: &Context, g: &mut G) {
+    use graphics::{Line, Rectangle};
+    let color = [0.0, 0.4, 0.8, 1.0];
+
+    for (position, queue) in queues {
+        let mut previous = position;
+        for waypoint in queue.waypoints.iter() {
+            Line::new(color, 1.0)
+                .draw([previous.x, previous.y, waypoint.x, waypoint.y], &c.draw_state, c.transform, g);
+            Rectangle::new(color)
+                .draw([waypoint.x - 2.0, waypoint.y - 2.0, 4.0, 4.0], &c.draw_state, c.transform, g);
+            previous = waypoint;
+        }
+
+        if let (true, Some(first)) = (queue.looping, queue.waypoints.front()) {
+            Line::new(color, 1.0)
+                .draw([previous.x, previous.y, first.x, first.y], &c.draw_state, c.transform, g);
+        }
+    }
+}
+
+// The part of the line hidden behind the first opaque object is drawn grey, with the blocking point marked.
+pub fn render_debug<G: Graphics>(line_object: &LineObject, c: &Context, g: &mut G) {
+    use graphics::{Line, Rectangle};
+    let a = &line_object.a;
+    let b = &line_object.b;
+
+    let point = match &line_object.line_of_sight {
+        LineOfSight::Clear => {
+            Line::new([255.0, 0.0, 0.2, 1.0], 1.0)
+                .draw([a.x, a.y, b.x, b.y], &c.draw_state, c.transform, g);
+            return;
+        },
+        LineOfSight::Blocked{point, ..} => point
+    };
+
     Line::new([255.0, 0.0, 0.2, 1.0], 1.0)
-        .draw(line, &c.draw_state, c.transform, g);
-}
\ No newline at end of file
+        .draw([a.x, a.y, point.x, point.y], &c.draw_state, c.transform, g);
+    Line::new([0.5, 0.5, 0.5, 1.0], 1.0)
+        .draw([point.x, point.y, b.x, b.y], &c.draw_state, c.transform, g);
+    Rectangle::new([255.0, 0.0, 0.2, 1.0])
+        .draw([point.x - 2.0, point.y - 2.0, 4.0, 4.0], &c.draw_state, c.transform, g);
+}
+
+// Capture areas and exit zones are outlined in the color of the team they're for.
+pub fn render_victory_areas<G: Graphics>(conditions: &[TeamCondition], team_colors: &HashMap<u32, [f32; 4]>, c: &Context, g: &mut G) {
+    use graphics::Rectangle;
+
+    for team_condition in conditions {
+        let area = match &team_condition.condition {
+            VictoryCondition::HoldArea{area, ..} | VictoryCondition::ReachExit{area, ..} => area,
+            _ => continue
+        };
+        let team = team_condition.team;
+        let color = *team_colors.get(&team).unwrap_or(&TEAM_PALETTE[team as usize % TEAM_PALETTE.len()]);
+        Rectangle::new_border(color, 2.0)
+            .draw([area.position.x, area.position.y, area.size.width, area.size.height], &c.draw_state, c.transform, g);
+    }
+}
+
+// Tints the whole window and puts a banner across its middle once the game is decided.
+pub fn render_result<G: Graphics>(result: GameResult, c: &Context, g: &mut G) {
+    use graphics::Rectangle;
+
+    let (tint, banner) = match result {
+        GameResult::Victory => ([0.0, 0.4, 0.0, 0.5], [0.0, 0.6, 0.0, 1.0]),
+        GameResult::Defeat => ([0.4, 0.0, 0.0, 0.5], [0.6, 0.0, 0.0, 1.0])
+    };
+    let [width, height] = c.get_view_size();
+    Rectangle::new(tint)
+        .draw([0.0, 0.0, width, height], &c.draw_state, c.transform, g);
+    Rectangle::new(banner)
+        .draw([0.0, height / 2.0 - 30.0, width, 60.0], &c.draw_state, c.transform, g);
+}
+
+pub fn render_selection_box<G: Graphics>(start: &Coordinates, end: &Coordinates, c: &Context, g: &mut G) {
+    use graphics::Rectangle;
+    let (position, size) = algebra_basics::get_rectangle_from_corners(start, end);
+    let dimensions = [position.x, position.y, size.width, size.height];
+    Rectangle::new_border([0.0, 0.6, 0.0, 1.0], 1.0)
+        .draw(dimensions, &c.draw_state, c.transform, g);
+}
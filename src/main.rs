@@ -5,11 +5,13 @@ extern crate opengl_graphics;
 
 use piston::window::WindowSettings;
 use glutin_window::GlutinWindow;
-use piston::event_loop::{Events, EventSettings, EventLoop};
+use piston::event_loop::{Events, EventSettings};
 use opengl_graphics::{OpenGL, GlGraphics};
 use piston::input::RenderEvent;
 
 use std::collections::HashMap;
+use std::env;
+use std::process;
 use std::time;
 
 pub mod game_controller;
@@ -19,16 +21,32 @@ pub mod testing;
 pub mod algebra_basics;
 pub mod debug_utils;
 
-use crate::game_data::gameboard::Gameboard;
-use crate::game_data::game_object::{GameObject, GameObjectType};
-use crate::algebra_basics::{Coordinates, Size};
+use crate::game_data::scenario;
+use crate::game_data::scenario::Scenario;
+use crate::algebra_basics::Coordinates;
 use crate::game_controller::game_managers::*;
 use crate::game_controller::utils::PistonEventTranslator;
+use crate::game_controller::formations::Formation;
+use crate::game_controller::pathfinder::Pathfinder;
+use crate::game_controller::snapshot;
+use crate::game_controller::interactions;
+use crate::game_controller::combat;
+use crate::game_controller::line_of_sight::LineOfSight;
+use crate::game_controller::fog_of_war;
+use crate::game_controller::turns;
+use crate::game_controller::turns::GameMode;
+use crate::game_controller::ai;
+use crate::game_controller::ai::{AiState, AiStateContainer};
+use crate::game_controller::order_queues;
+use crate::game_controller::rules;
+use crate::game_controller::rules::Rules;
 use crate::debug_utils::{DebugState, LineObject, ConsoleCommand};
 
 struct MainState {
     basic_state: BasicState,
-    debug_state: DebugState
+    debug_state: DebugState,
+    ai_state: AiState,
+    team_colors: HashMap<u32, [f32; 4]>
 }
 
 impl BasicStateContainer for MainState {
@@ -37,23 +55,53 @@ impl BasicStateContainer for MainState {
     }
 }
 
-fn build_state() -> MainState {
-    let mut gameboard = Gameboard::new();
-    let game_object0 = GameObject::new(GameObjectType::Selectable, Coordinates::new(0.0, 0.0), Size::new(50.0, 50.0));
-    let game_object1 = GameObject::new(GameObjectType::Selectable, Coordinates::new(60.0, 0.0), Size::new(50.0, 150.0));
-    gameboard.add_object(game_object0);
-    gameboard.add_object(game_object1);
+impl AiStateContainer for MainState {
+    fn get_ai_state(&mut self) -> &mut AiState {
+        &mut self.ai_state
+    }
+}
+
+fn load_scenario() -> Scenario {
+    let path = env::args().nth(1).unwrap_or_else(|| scenario::DEFAULT_SCENARIO_PATH.to_string());
+
+    match scenario::load_from_file(&path) {
+        Ok(scenario) => scenario,
+        Err(error) => {
+            eprintln!("Could not load scenario {}: {}", path, error);
+            process::exit(1);
+        }
+    }
+}
+
+fn build_state(scenario: Scenario) -> MainState {
+    let mut gameboard = scenario.gameboard;
+    fog_of_war::update_visibility(&mut gameboard);
+    let game_mode = if scenario.turn_based {
+        GameMode::turn_based(&mut gameboard, scenario.local_team)
+    } else {
+        GameMode::RealTime
+    };
 
     let basic_state = BasicState {
-        current_selected_id: 0,
+        selected_ids: scenario.initial_selection,
         external_event: UserInput::NoInputCursorPos(Coordinates::new(0.0, 0.0)),
-        gameboard: gameboard,
-        movements: HashMap::new()
+        gameboard,
+        movements: HashMap::new(),
+        interaction_orders: HashMap::new(),
+        attack_orders: HashMap::new(),
+        order_queues: HashMap::new(),
+        local_team: scenario.local_team,
+        game_mode,
+        rules: Rules::new(scenario.victory_conditions),
+        formation: Formation::Line,
+        pathfinder: Pathfinder::Detour,
+        game_events: Vec::new()
     };
 
     let initial_line = LineObject {
         a: Coordinates::new(0.0, 0.0),
-        b: Coordinates::new(0.0, 0.0)
+        b: Coordinates::new(0.0, 0.0),
+        line_of_sight: LineOfSight::Clear
     };
 
     let debug_state = DebugState {
@@ -67,26 +115,34 @@ fn build_state() -> MainState {
 
     MainState {
         basic_state,
-        debug_state
+        debug_state,
+        ai_state: AiState::new(scenario.ai_seed),
+        team_colors: scenario.team_colors
     }
 }
 
 fn build_piston_translator() -> PistonEventTranslator {
     PistonEventTranslator {
-        current_cursor_pos: Coordinates::new(0.0, 0.0)
+        current_cursor_pos: Coordinates::new(0.0, 0.0),
+        drag_start: None,
+        shift_pressed: false
     }
 }
 
 fn main() {
+    let scenario = load_scenario();
+    let bounds = scenario.gameboard.get_bounds();
+    let window_size = [bounds.width as u32, bounds.height as u32];
+
     let opengl = OpenGL::V3_2;
-    let settings = WindowSettings::new("Tactical Squares", [512; 2])
+    let settings = WindowSettings::new("Tactical Squares", window_size)
         .graphics_api(opengl)
         .exit_on_esc(true);
 
     let mut window: GlutinWindow = settings.build()
         .expect("Could not create window");
 
-    let mut state = build_state();
+    let mut state = build_state(scenario);
     let mut translator = build_piston_translator();
 
     let mut events = Events::new(EventSettings::new());
@@ -94,24 +150,68 @@ fn main() {
 
     while let Some(e) = events.next(&mut window) {
         state.basic_state.external_event = translator.piston_event_to_internal_user_input(&e);
-        state = process_selection(state);
-        state = process_player_movement(state);
-        state = proces_movement(state);
+        state = clear_game_events(state);
+        // The board freezes once the game is decided, only the result screen is left.
+        if state.basic_state.rules.get_winner().is_none() {
+            state = process_selection(state);
+            state = process_formation_selection(state);
+            state = turns::process_path_preview(state);
+            state = process_player_movement(state);
+            state = order_queues::process_order_queue_input(state);
+            state = ai::process_ai(state);
+            state = proces_movement(state);
+            state = order_queues::process_order_queues(state);
+            state = interactions::process_interaction_orders(state);
+            state = combat::process_attack_orders(state);
+            state = fog_of_war::process_visibility(state);
+            state = turns::process_turns(state);
+            state = rules::process_rules(state);
+            state = snapshot::process_quick_save_and_load(state);
+        }
         
         // debug
         state.debug_state = debug_utils::process_debug_line(&state.basic_state, state.debug_state);
         state.debug_state = debug_utils::process_debug_enabled(&state.basic_state, state.debug_state);
         debug_utils::print_object_positions_and_sizes(&state.basic_state, &mut state.debug_state);
         state.debug_state = debug_utils::process_console_command(state.debug_state);
-        state.basic_state = debug_utils::apply_console_command_to_basic_state(state.basic_state, &state.debug_state);
+        if let Err(error) = debug_utils::apply_console_command_to_basic_state(&mut state.basic_state, &mut state.ai_state, &mut state.debug_state) {
+            println!("Error: {}", error);
+        }
 
         if let Some(args) = e.render_args() {
             gl.draw(args.viewport(), |c, g| {
                 use graphics::{clear};
-                gameboard_view::render(state.get_basic_state().gameboard.get_all_objects(), &c, g);
-                gameboard_view::render_debug(&state.debug_state.debug_line, &c, g);
                 clear([1.0; 4], g);
+                let gameboard = &state.basic_state.gameboard;
+                let local_team = state.basic_state.local_team;
+                let objects = gameboard.get_objects_visible_to(local_team)
+                    .into_iter()
+                    .filter_map(|id| gameboard.get_object_by_id(id))
+                    .collect();
+                gameboard_view::render(objects, &state.team_colors, &c, g);
+                if let Some(map) = gameboard.get_visibility(local_team) {
+                    gameboard_view::render_fog(map, &c, g);
+                }
+                // Only the queues of the own units are shown.
+                let queues: Vec<_> = state.basic_state.order_queues.iter()
+                    .filter(|(id, _)| gameboard.get_team(**id) == Some(local_team))
+                    .filter_map(|(id, queue)| gameboard.get_object_by_id(*id).map(|unit| (unit.position.clone(), queue)))
+                    .collect();
+                gameboard_view::render_order_queues(&queues, &c, g);
+                gameboard_view::render_victory_areas(state.basic_state.rules.get_conditions(), &state.team_colors, &c, g);
+                if let GameMode::TurnBased(turn) = &state.basic_state.game_mode {
+                    gameboard_view::render_path_previews(&turn.previews, &c, g);
+                }
+                gameboard_view::render_debug(&state.debug_state.debug_line, &c, g);
+                if let Some((start, end)) = translator.get_drag_box() {
+                    gameboard_view::render_selection_box(&start, &end, &c, g);
+                }
+                if let Some(result) = state.basic_state.rules.get_result(local_team) {
+                    gameboard_view::render_result(result, &c, g);
+                }
             });
         }
     }
+    // Lets scripts running the game tell how it ended.
+    process::exit(rules::get_exit_code(state.basic_state.rules.get_result(state.basic_state.local_team)));
 }
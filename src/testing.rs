@@ -2,22 +2,38 @@ pub mod setup {
     use std::collections::HashMap;
     
     use crate::game_data::game_object::{GameObject, GameObjectType};
+    use crate::game_data::components::Team;
     use crate::algebra_basics::{Coordinates, Size};
     use crate::game_data::gameboard::Gameboard;
     use crate::game_controller::game_managers::{BasicState, BasicStateContainer, UserInput};
+    use crate::game_controller::formations::Formation;
+    use crate::game_controller::pathfinder::Pathfinder;
+    use crate::game_controller::turns::GameMode;
+    use crate::game_controller::rules::Rules;
+    use crate::game_controller::ai::{AiState, AiStateContainer};
 
     pub fn setup_selectable_object() -> GameObject {
-        GameObject::new(GameObjectType::Selectable, Coordinates::new(0.0, 0.0), Size::new(50.0, 50.0))
+        setup_selectable_object_at(Coordinates::new(0.0, 0.0))
+    }
+
+    pub const LOCAL_TEAM: u32 = 0;
+
+    // A unit of the local team.
+    pub fn setup_selectable_object_at(position: Coordinates) -> GameObject {
+        let mut object = GameObject::new(GameObjectType::Selectable, position, Size::new(50.0, 50.0));
+        object.components.insert(Team{id: LOCAL_TEAM});
+        object
     }
 
     pub fn setup_gameboard_with_selectable_object() -> Gameboard {
         let mut gameboard = Gameboard::new();
-        gameboard.add_object(setup_selectable_object());
+        gameboard.add_object(setup_selectable_object()).unwrap();
         gameboard
     }
 
     pub struct MockMainState {
-        pub basic_state: BasicState
+        pub basic_state: BasicState,
+        pub ai_state: AiState
     }
 
     impl BasicStateContainer for MockMainState {
@@ -26,17 +42,33 @@ pub mod setup {
         }
     }
 
+    impl AiStateContainer for MockMainState {
+        fn get_ai_state(&mut self) -> &mut AiState {
+            return &mut self.ai_state;
+        }
+    }
+
     pub fn setup_game_state_with_one_object() -> MockMainState {
-        let mut gameboard = setup_gameboard_with_selectable_object();
+        let gameboard = setup_gameboard_with_selectable_object();
         let basic = BasicState {
-            current_selected_id: 0,
+            selected_ids: vec![0].into_iter().collect(),
             external_event: UserInput::NoInputCursorPos(Coordinates::new(0.0, 0.0)),
-            gameboard: gameboard,
-            movements: HashMap::new()
+            gameboard,
+            movements: HashMap::new(),
+            interaction_orders: HashMap::new(),
+            attack_orders: HashMap::new(),
+            order_queues: HashMap::new(),
+            local_team: LOCAL_TEAM,
+            game_mode: GameMode::RealTime,
+            rules: Rules::default(),
+            formation: Formation::Line,
+            pathfinder: Pathfinder::Detour,
+            game_events: Vec::new()
         };
 
         MockMainState {
-            basic_state: basic
+            basic_state: basic,
+            ai_state: AiState::new(0)
         }
     }
 }
\ No newline at end of file